@@ -0,0 +1,84 @@
+use crate::{Bitboard, FILES};
+
+pub(crate) const KNIGHT_ATTACKS: [Bitboard; 64] = knight_attack_table();
+
+pub(crate) const KING_ATTACKS: [Bitboard; 64] = king_attack_table();
+
+pub(crate) const PAWN_ATTACKS: [[Bitboard; 64]; 2] =
+    [pawn_attack_table(true), pawn_attack_table(false)];
+
+const fn king_attack_table() -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+    let mut square = 0;
+    while square < 64 {
+        let bit: u64 = 1 << square;
+        table[square] = Bitboard(
+            (bit << 8)
+                | (bit >> 8)
+                | ((bit << 1) & !FILES[0].0)
+                | ((bit >> 1) & !FILES[7].0)
+                | ((bit << 9) & !FILES[0].0)
+                | ((bit << 7) & !FILES[7].0)
+                | ((bit >> 7) & !FILES[0].0)
+                | ((bit >> 9) & !FILES[7].0),
+        );
+        square += 1;
+    }
+    table
+}
+
+const fn knight_attack_table() -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+    let mut square = 0;
+    while square < 64 {
+        let bit: u64 = 1 << square;
+        table[square] = Bitboard(
+            ((bit << 17) & !FILES[0].0)
+                | ((bit << 15) & !FILES[7].0)
+                | ((bit << 10) & !(FILES[0].0 | FILES[1].0))
+                | ((bit << 6) & !(FILES[6].0 | FILES[7].0))
+                | ((bit >> 17) & !FILES[7].0)
+                | ((bit >> 15) & !FILES[0].0)
+                | ((bit >> 10) & !(FILES[6].0 | FILES[7].0))
+                | ((bit >> 6) & !(FILES[0].0 | FILES[1].0)),
+        );
+        square += 1;
+    }
+    table
+}
+
+const fn pawn_attack_table(white: bool) -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+    let mut square = 0;
+    while square < 64 {
+        let bit: u64 = 1 << square;
+        table[square] = Bitboard(if white {
+            ((bit << 9) & !FILES[0].0) | ((bit << 7) & !FILES[7].0)
+        } else {
+            ((bit >> 7) & !FILES[0].0) | ((bit >> 9) & !FILES[7].0)
+        });
+        square += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        attacks::{KING_ATTACKS, KNIGHT_ATTACKS, PAWN_ATTACKS},
+        Color::*,
+    };
+
+    #[test]
+    fn hand_computed_entries() {
+        // knight on a1 attacks b3 and c2
+        assert_eq!((1 << 17) | (1 << 10), KNIGHT_ATTACKS[0]);
+        // king on h8 attacks g8, g7 and h7
+        assert_eq!((1 << 62) | (1 << 54) | (1 << 55), KING_ATTACKS[63]);
+        // e4 pawn attacks d5 and f5 for white, d3 and f3 for black
+        assert_eq!((1 << 35) | (1 << 37), PAWN_ATTACKS[White.index()][28]);
+        assert_eq!((1 << 19) | (1 << 21), PAWN_ATTACKS[Black.index()][28]);
+        // a-file pawns never wrap onto the h-file
+        assert_eq!(1 << 17, PAWN_ATTACKS[White.index()][8]);
+    }
+}
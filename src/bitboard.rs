@@ -0,0 +1,438 @@
+use alloc::vec::Vec;
+use core::{
+    fmt::Display,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not},
+};
+
+use crate::{Color, GameState, Piece, Square};
+
+const NOT_FILE_A: u64 = 0xFEFE_FEFE_FEFE_FEFE;
+const NOT_FILE_H: u64 = 0x7F7F_7F7F_7F7F_7F7F;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    pub const fn from_square(square: usize) -> Bitboard {
+        Bitboard(1 << square)
+    }
+
+    pub const fn contains(self, square: usize) -> bool {
+        self.0 & (1 << square) != 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub const fn first(self) -> Option<usize> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some(self.0.trailing_zeros() as usize)
+        }
+    }
+
+    pub const fn last(self) -> Option<usize> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some(63 - self.0.leading_zeros() as usize)
+        }
+    }
+
+    pub fn iter_squares(self) -> BitboardIter {
+        BitboardIter(self)
+    }
+
+    pub const fn north(self) -> Bitboard {
+        Bitboard(self.0 << 8)
+    }
+
+    pub const fn south(self) -> Bitboard {
+        Bitboard(self.0 >> 8)
+    }
+
+    pub const fn east(self) -> Bitboard {
+        Bitboard((self.0 << 1) & NOT_FILE_A)
+    }
+
+    pub const fn west(self) -> Bitboard {
+        Bitboard((self.0 >> 1) & NOT_FILE_H)
+    }
+
+    pub const fn flip_vertical(self) -> Bitboard {
+        Bitboard(self.0.swap_bytes())
+    }
+
+    pub const fn flip_horizontal(self) -> Bitboard {
+        Bitboard(self.0.reverse_bits().swap_bytes())
+    }
+}
+
+impl From<u64> for Bitboard {
+    fn from(bits: u64) -> Self {
+        Bitboard(bits)
+    }
+}
+
+impl From<Bitboard> for u64 {
+    fn from(bitboard: Bitboard) -> Self {
+        bitboard.0
+    }
+}
+
+impl From<Square> for Bitboard {
+    fn from(square: Square) -> Self {
+        Bitboard::from_square(square.index())
+    }
+}
+
+impl PartialEq<u64> for Bitboard {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Bitboard> for u64 {
+    fn eq(&self, other: &Bitboard) -> bool {
+        *self == other.0
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(self, rhs: Bitboard) -> Bitboard {
+        Bitboard(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(self, rhs: Bitboard) -> Bitboard {
+        Bitboard(self.0 | rhs.0)
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Bitboard;
+
+    fn bitxor(self, rhs: Bitboard) -> Bitboard {
+        Bitboard(self.0 ^ rhs.0)
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, rhs: Bitboard) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Bitboard) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitXorAssign for Bitboard {
+    fn bitxor_assign(&mut self, rhs: Bitboard) {
+        self.0 ^= rhs.0;
+    }
+}
+
+impl IntoIterator for Bitboard {
+    type Item = usize;
+    type IntoIter = BitboardIter;
+
+    fn into_iter(self) -> BitboardIter {
+        BitboardIter(self)
+    }
+}
+
+impl Display for Bitboard {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for rank in (0..8).rev() {
+            for file in 0..8 {
+                let mark = if self.contains(rank * 8 + file) {
+                    'x'
+                } else {
+                    '.'
+                };
+                if file > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{mark}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BitboardIter(Bitboard);
+
+impl Iterator for BitboardIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let square = self.0.first()?;
+        self.0 .0 &= self.0 .0 - 1;
+        Some(square)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.0.count() as usize;
+        (count, Some(count))
+    }
+}
+
+impl ExactSizeIterator for BitboardIter {}
+
+impl DoubleEndedIterator for BitboardIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let square = self.0.last()?;
+        self.0 .0 &= !(1 << square);
+        Some(square)
+    }
+}
+
+pub fn squares(bitboard: Bitboard) -> BitboardIter {
+    BitboardIter(bitboard)
+}
+
+pub fn bit(square: Square) -> Bitboard {
+    Bitboard::from(square)
+}
+
+pub fn is_set(bitboard: Bitboard, square: Square) -> bool {
+    bitboard.contains(square.index())
+}
+
+pub(crate) fn occupancy_of(bitboards: &[Bitboard; 12]) -> [Bitboard; 2] {
+    let mut occupancy = [Bitboard::EMPTY; 2];
+    for (index, bitboard) in bitboards.iter().enumerate() {
+        occupancy[index % 2] |= *bitboard;
+    }
+    occupancy
+}
+
+impl GameState {
+    pub fn count(&self, piece: Piece) -> u32 {
+        self.bitboards[piece.index()].count()
+    }
+
+    // Squares come back in ascending order, a1 first and h8 last.
+    pub fn piece_squares(&self, piece: Piece) -> Vec<Square> {
+        self.bitboards[piece.index()]
+            .iter_squares()
+            .filter_map(Square::from_index)
+            .collect()
+    }
+
+    pub fn occupied(&self, square: Square) -> bool {
+        is_set(self.all_occupancy(), square)
+    }
+
+    pub fn occupied_by(&self, color: Color) -> Bitboard {
+        self.occupancy[color.index()]
+    }
+
+    pub fn empty(&self) -> Bitboard {
+        !self.all_occupancy()
+    }
+
+    pub(crate) fn put_piece(&mut self, piece: Piece, square: usize) {
+        let bit = Bitboard::from_square(square);
+        self.bitboards[piece.index()] |= bit;
+        self.occupancy[piece.color().index()] |= bit;
+    }
+
+    pub(crate) fn remove_piece(&mut self, piece: Piece, square: usize) {
+        let bit = Bitboard::from_square(square);
+        self.bitboards[piece.index()] &= !bit;
+        self.occupancy[piece.color().index()] &= !bit;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::ToString, vec::Vec};
+
+    use crate::{
+        bit, is_set, occupancy_of, squares, Bitboard, Color::*, File, GameState, Piece::*, Rank,
+        Square,
+    };
+
+    #[test]
+    fn squares_of_white_pawns() {
+        let game_state = GameState::default();
+        let pawns: Vec<usize> = squares(*game_state.get_bitboard(Pawn(White))).collect();
+        assert_eq!((8..16).collect::<Vec<_>>(), pawns);
+    }
+
+    #[test]
+    fn squares_of_empty_and_full_bitboards() {
+        assert_eq!(None, squares(Bitboard::EMPTY).next());
+        assert_eq!(0, squares(Bitboard::EMPTY).len());
+        assert_eq!(
+            vec![0, 63],
+            squares(Bitboard(0x8000000000000001)).collect::<Vec<_>>()
+        );
+        assert_eq!(64, squares(Bitboard::FULL).count());
+    }
+
+    #[test]
+    fn bit_and_is_set() {
+        let e4 = Square::new(File::E, Rank::Fourth);
+        assert_eq!(1 << 28, bit(e4));
+        assert!(is_set(Bitboard(0x0000000010000000), e4));
+        assert!(!is_set(Bitboard(0x0000000008000000), e4));
+    }
+
+    #[test]
+    fn count_and_occupied() {
+        let game_state = GameState::default();
+        assert_eq!(8, game_state.count(Pawn(White)));
+        assert_eq!(1, game_state.count(King(Black)));
+        assert!(game_state.occupied(Square::new(File::E, Rank::First)));
+        assert!(!game_state.occupied(Square::new(File::E, Rank::Fourth)));
+    }
+
+    #[test]
+    fn piece_squares() {
+        let game_state = GameState::default();
+        assert_eq!(
+            vec![
+                Square::new(File::B, Rank::First),
+                Square::new(File::G, Rank::First)
+            ],
+            game_state.piece_squares(Knight(White))
+        );
+        assert_eq!(
+            vec![Square::new(File::E, Rank::Eighth)],
+            game_state.piece_squares(King(Black))
+        );
+        let game_state = "4k3/8/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(game_state.piece_squares(Queen(White)).is_empty());
+    }
+
+    #[test]
+    fn bitboard_queries() {
+        let bitboard = Bitboard(0x0000_0000_1000_0081);
+        assert_eq!(3, bitboard.count());
+        assert_eq!(Some(0), bitboard.first());
+        assert_eq!(Some(28), bitboard.last());
+        assert!(bitboard.contains(7));
+        assert!(!bitboard.contains(8));
+        assert_eq!(vec![0, 7, 28], bitboard.iter_squares().collect::<Vec<_>>());
+        assert_eq!(
+            vec![28, 7, 0],
+            bitboard.into_iter().rev().collect::<Vec<_>>()
+        );
+        assert_eq!(None, Bitboard::EMPTY.first());
+        assert_eq!(None, Bitboard::EMPTY.last());
+        assert!(Bitboard::EMPTY.is_empty());
+        assert_eq!(Bitboard::EMPTY, !Bitboard::FULL);
+        assert_eq!(Bitboard(0x81), bitboard & Rank::First.bitboard());
+        assert_eq!(Bitboard(0x1000_0000), bitboard ^ Bitboard(0x81));
+    }
+
+    #[test]
+    fn shifts_mask_file_wraps() {
+        let h_file = File::H.bitboard();
+        let a_file = File::A.bitboard();
+        assert_eq!(Bitboard::EMPTY, h_file.east());
+        assert_eq!(Bitboard::EMPTY, a_file.west());
+        assert_eq!(File::G.bitboard(), h_file.west());
+        assert_eq!(File::B.bitboard(), a_file.east());
+        assert_eq!(Rank::Second.bitboard(), Rank::First.bitboard().north());
+        assert_eq!(Bitboard::EMPTY, Rank::First.bitboard().south());
+        assert_eq!(Bitboard::EMPTY, Rank::Eighth.bitboard().north());
+        let e4 = Bitboard::from(Square::new(File::E, Rank::Fourth));
+        assert_eq!(
+            Bitboard::from(Square::new(File::E, Rank::Fifth)),
+            e4.north()
+        );
+        assert_eq!(Bitboard::from_square(27), e4.west());
+    }
+
+    #[test]
+    fn bitboard_display_grid() {
+        let e4 = bit(Square::new(File::E, Rank::Fourth));
+        let expected = "\
+. . . . . . . .
+. . . . . . . .
+. . . . . . . .
+. . . . . . . .
+. . . . x . . .
+. . . . . . . .
+. . . . . . . .
+. . . . . . . .
+";
+        assert_eq!(expected, e4.to_string());
+    }
+
+    #[test]
+    fn occupancy_accessors() {
+        let game_state = GameState::default();
+        assert_eq!(
+            Rank::First.bitboard() | Rank::Second.bitboard(),
+            game_state.occupied_by(White)
+        );
+        assert_eq!(
+            Rank::Seventh.bitboard() | Rank::Eighth.bitboard(),
+            game_state.occupied_by(Black)
+        );
+        assert_eq!(32, game_state.all_occupancy().count());
+        assert_eq!(!game_state.all_occupancy(), game_state.empty());
+    }
+
+    #[test]
+    fn occupancy_is_maintained_through_make_and_unmake() {
+        let check = |state: &GameState| {
+            assert_eq!(occupancy_of(&state.bitboards), state.occupancy, "{state}");
+        };
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        ] {
+            let mut state = fen.parse::<GameState>().unwrap();
+            check(&state);
+            check(&state.flipped());
+            check(&state.mirror_files());
+            for mv in state.legal_moves() {
+                let before = state.clone();
+                let undo = state.make_move(mv).unwrap();
+                check(&state);
+                for reply in state.legal_moves() {
+                    let next = state.play_move(reply).unwrap();
+                    check(&next);
+                }
+                state.unmake_move(mv, undo);
+                assert_eq!(before, state);
+            }
+        }
+    }
+}
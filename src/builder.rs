@@ -0,0 +1,136 @@
+use alloc::{format, string::String};
+
+use crate::{
+    occupancy_of, Bitboard, Color, GameState, Piece, Piece::*, Square, CLASSIC_ROOK_FILES,
+};
+
+#[derive(Clone, Debug)]
+pub struct GameStateBuilder {
+    bitboards: [Bitboard; 12],
+    active_color: Color,
+    castling_rights: [bool; 4],
+    en_passant_target: Option<Square>,
+    half_move_clock: u16,
+    full_move_number: u16,
+}
+
+impl GameStateBuilder {
+    pub fn new() -> Self {
+        GameStateBuilder {
+            bitboards: [Bitboard::EMPTY; 12],
+            active_color: Color::White,
+            castling_rights: [false; 4],
+            en_passant_target: None,
+            half_move_clock: 0,
+            full_move_number: 1,
+        }
+    }
+
+    pub fn place(mut self, square: Square, piece: Piece) -> Self {
+        let bit = Bitboard::from(square);
+        for bitboard in self.bitboards.iter_mut() {
+            *bitboard &= !bit;
+        }
+        self.bitboards[piece.index()] |= bit;
+        self
+    }
+
+    pub fn active_color(mut self, color: Color) -> Self {
+        self.active_color = color;
+        self
+    }
+
+    pub fn castling(mut self, rights: [bool; 4]) -> Self {
+        self.castling_rights = rights;
+        self
+    }
+
+    pub fn en_passant(mut self, target: Option<Square>) -> Self {
+        self.en_passant_target = target;
+        self
+    }
+
+    pub fn clocks(mut self, half_move_clock: u16, full_move_number: u16) -> Self {
+        self.half_move_clock = half_move_clock;
+        self.full_move_number = full_move_number;
+        self
+    }
+
+    pub fn build(self) -> Result<GameState, String> {
+        for color in Color::ALL {
+            let kings = self.bitboards[King(color).index()].count();
+            if kings != 1 {
+                return Err(format!("{color:?} has {kings} kings"));
+            }
+        }
+        Ok(GameState {
+            bitboards: self.bitboards,
+            occupancy: occupancy_of(&self.bitboards),
+            active_color: self.active_color,
+            castling_rights: self.castling_rights,
+            castling_rook_files: CLASSIC_ROOK_FILES,
+            en_passant_target: self.en_passant_target.map(|square| square.index() as u8),
+            half_move_clock: self.half_move_clock,
+            full_move_number: self.full_move_number,
+        })
+    }
+}
+
+impl Default for GameStateBuilder {
+    fn default() -> Self {
+        GameStateBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use crate::{Color::*, File, GameState, GameStateBuilder, Piece::*, Rank, Square};
+
+    #[test]
+    fn king_and_pawn_endgame() {
+        let game_state = GameStateBuilder::new()
+            .place(Square::new(File::E, Rank::First), King(White))
+            .place(Square::new(File::E, Rank::Fourth), Pawn(White))
+            .place(Square::new(File::E, Rank::Eighth), King(Black))
+            .active_color(Black)
+            .en_passant(Some(Square::new(File::E, Rank::Third)))
+            .clocks(0, 12)
+            .build();
+        assert_eq!(
+            Ok("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 12"
+                .parse::<GameState>()
+                .unwrap()),
+            game_state
+        );
+    }
+
+    #[test]
+    fn build_checks_kings() {
+        let builder = GameStateBuilder::new()
+            .place(Square::new(File::E, Rank::First), King(White))
+            .castling([true, true, false, false]);
+        assert!(builder.clone().build().is_err());
+        let game_state = builder
+            .place(Square::new(File::E, Rank::Eighth), King(Black))
+            .place(Square::new(File::A, Rank::First), Rook(White))
+            .place(Square::new(File::H, Rank::First), Rook(White))
+            .build()
+            .unwrap();
+        assert_eq!("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", game_state.to_string());
+        let two_kings = GameStateBuilder::new()
+            .place(Square::new(File::E, Rank::First), King(White))
+            .place(Square::new(File::D, Rank::First), King(White))
+            .place(Square::new(File::E, Rank::Eighth), King(Black))
+            .build();
+        assert!(two_kings.is_err());
+        let replaced = GameStateBuilder::new()
+            .place(Square::new(File::E, Rank::First), Queen(White))
+            .place(Square::new(File::E, Rank::First), King(White))
+            .place(Square::new(File::E, Rank::Eighth), King(Black))
+            .build()
+            .unwrap();
+        assert_eq!(0, *replaced.get_bitboard(Queen(White)));
+    }
+}
@@ -0,0 +1,121 @@
+use alloc::{format, string::String};
+
+use crate::GameState;
+
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+fn back_rank(index: u16) -> [char; 8] {
+    let mut rank = [' '; 8];
+    let index = index as usize;
+    rank[index % 4 * 2 + 1] = 'B';
+    rank[index / 4 % 4 * 2] = 'B';
+    let mut place = |piece, nth: usize| {
+        let file = (0..8).filter(|&file| rank[file] == ' ').nth(nth).unwrap();
+        rank[file] = piece;
+    };
+    let rest = index / 16;
+    place('Q', rest % 6);
+    let (first, second) = KNIGHT_PLACEMENTS[rest / 6];
+    place('N', second);
+    place('N', first);
+    for piece in ['R', 'K', 'R'] {
+        place(piece, 0);
+    }
+    rank
+}
+
+impl GameState {
+    pub fn chess960(index: u16) -> Option<GameState> {
+        if index >= 960 {
+            return None;
+        }
+        let rank = back_rank(index);
+        let white: String = rank.iter().collect();
+        let castling: String = (0..8)
+            .rev()
+            .filter(|&file| rank[file] == 'R')
+            .map(|file| (b'A' + file as u8) as char)
+            .chain(
+                (0..8)
+                    .rev()
+                    .filter(|&file| rank[file] == 'R')
+                    .map(|file| (b'a' + file as u8) as char),
+            )
+            .collect();
+        format!(
+            "{}/pppppppp/8/8/8/8/PPPPPPPP/{white} w {castling} - 0 1",
+            white.to_lowercase()
+        )
+        .parse()
+        .ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use crate::GameState;
+
+    #[test]
+    fn scharnagl_numbering() {
+        assert_eq!(Some(GameState::default()), GameState::chess960(518));
+        assert_eq!(
+            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1",
+            GameState::chess960(0).unwrap().to_string()
+        );
+        assert_eq!(
+            "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/RKRNNQBB w CAca - 0 1",
+            GameState::chess960(959).unwrap().to_string()
+        );
+        assert_eq!(None, GameState::chess960(960));
+    }
+
+    #[test]
+    fn every_start_position_is_distinct_and_valid() {
+        let positions: Vec<GameState> = (0..960).filter_map(GameState::chess960).collect();
+        assert_eq!(960, positions.len());
+        let mut ranks: Vec<String> = positions
+            .iter()
+            .map(|position| position.to_string())
+            .collect();
+        ranks.sort();
+        ranks.dedup();
+        assert_eq!(960, ranks.len());
+        for position in &positions {
+            assert_eq!(Ok(()), position.validate());
+            assert_eq!(
+                position.legal_move_count(),
+                position.flipped().legal_move_count()
+            );
+        }
+    }
+
+    #[test]
+    fn chess960_castling() {
+        // Index 0: BBQNNRKR; clear the knights and queen so both castlings are open.
+        let game_state = "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BB3RKR w HFhf - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let castled = game_state.play_moves(&["O-O-O"]).unwrap();
+        assert_eq!(
+            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBKR3R b hf - 1 1",
+            castled.to_string()
+        );
+        assert_eq!(400, GameState::chess960(0).unwrap().perft(2));
+    }
+}
@@ -0,0 +1,247 @@
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Display, str::FromStr};
+
+use crate::{FenError, GameState, Move, MoveParseError};
+
+type Operation = (String, Vec<String>);
+
+pub fn parse_epd(epd: &str) -> Result<(GameState, Vec<(String, String)>), FenError> {
+    let (game_state, operations) = Epd::parse(epd)?;
+    let operations = operations
+        .into_iter()
+        .map(|(opcode, operands)| (opcode, operands.join(" ")))
+        .collect();
+    Ok((game_state, operations))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Epd {
+    pub game_state: GameState,
+    pub operations: BTreeMap<String, Vec<String>>,
+}
+
+fn split_operations(text: &str) -> Vec<Vec<String>> {
+    let mut operations = Vec::new();
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    for char in text.chars() {
+        match char {
+            '"' if quoted => {
+                tokens.push(core::mem::take(&mut token));
+                quoted = false;
+            }
+            '"' => quoted = true,
+            _ if quoted => token.push(char),
+            ';' | ' ' | '\t' => {
+                if !token.is_empty() {
+                    tokens.push(core::mem::take(&mut token));
+                }
+                if char == ';' && !tokens.is_empty() {
+                    operations.push(core::mem::take(&mut tokens));
+                }
+            }
+            _ => token.push(char),
+        }
+    }
+    if !token.is_empty() || quoted {
+        tokens.push(token);
+    }
+    if !tokens.is_empty() {
+        operations.push(tokens);
+    }
+    operations
+}
+
+impl Epd {
+    // Operations are returned in input order, duplicates included, for
+    // parse_epd; FromStr keys them by opcode.
+    fn parse(epd: &str) -> Result<(GameState, Vec<Operation>), FenError> {
+        let mut fields = epd.trim().splitn(5, ' ');
+        let board: Vec<&str> = fields.by_ref().take(4).collect();
+        let game_state = board.join(" ").parse::<GameState>()?;
+        let operations = split_operations(fields.next().unwrap_or(""))
+            .into_iter()
+            .map(|mut tokens| {
+                let opcode = tokens.remove(0);
+                (opcode, tokens)
+            })
+            .collect();
+        Ok((game_state, operations))
+    }
+
+    pub fn operation(&self, opcode: &str) -> Option<&[String]> {
+        self.operations.get(opcode).map(Vec::as_slice)
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.operation("id")?.first().map(String::as_str)
+    }
+
+    pub fn centipawns(&self) -> Option<i32> {
+        self.operation("ce")?.first()?.parse().ok()
+    }
+
+    pub fn best_moves(&self) -> Result<Vec<Move>, MoveParseError> {
+        self.san_operands("bm")
+    }
+
+    pub fn avoid_moves(&self) -> Result<Vec<Move>, MoveParseError> {
+        self.san_operands("am")
+    }
+
+    pub fn pv(&self) -> Result<Vec<Move>, MoveParseError> {
+        let mut game_state = self.game_state.clone();
+        let mut moves = Vec::new();
+        for san in self.operation("pv").unwrap_or_default() {
+            let mv = game_state.parse_san(san)?;
+            game_state = game_state
+                .play_move(mv)
+                .map_err(|_| MoveParseError::Illegal(san.clone()))?;
+            moves.push(mv);
+        }
+        Ok(moves)
+    }
+
+    fn san_operands(&self, opcode: &str) -> Result<Vec<Move>, MoveParseError> {
+        self.operation(opcode)
+            .unwrap_or_default()
+            .iter()
+            .map(|san| self.game_state.parse_san(san))
+            .collect()
+    }
+}
+
+impl FromStr for Epd {
+    type Err = FenError;
+
+    fn from_str(epd: &str) -> Result<Self, Self::Err> {
+        let (game_state, operations) = Epd::parse(epd)?;
+        Ok(Epd {
+            game_state,
+            operations: operations.into_iter().collect(),
+        })
+    }
+}
+
+impl Display for Epd {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let fen = self.game_state.to_string();
+        let board: Vec<&str> = fen.split(' ').take(4).collect();
+        write!(f, "{}", board.join(" "))?;
+        for (opcode, operands) in &self.operations {
+            write!(f, " {opcode}")?;
+            for operand in operands {
+                if operand.is_empty() || operand.contains([' ', ';']) {
+                    write!(f, " \"{operand}\"")?;
+                } else {
+                    write!(f, " {operand}")?;
+                }
+            }
+            write!(f, ";")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use crate::{parse_epd, Epd, FenError, GameState, Move, MoveParseError};
+
+    #[test]
+    fn parse_epd_operations() {
+        let (game_state, operations) = parse_epd(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - bm Nf3; id \"test 1\";",
+        )
+        .unwrap();
+        assert_eq!(GameState::default(), game_state);
+        assert_eq!(
+            vec![
+                ("bm".to_string(), "Nf3".to_string()),
+                ("id".to_string(), "test 1".to_string())
+            ],
+            operations
+        );
+        assert_eq!(Ok(Move::new(12, 28, None)), game_state.parse_san("e4"));
+    }
+
+    #[test]
+    fn parse_epd_quoted_operands() {
+        let (_, operations) =
+            parse_epd("4k3/8/8/8/8/8/8/4K3 w - - c0 \"a; b\"; id \"say \"; am Kd1 Kf1;").unwrap();
+        assert_eq!(
+            vec![
+                ("c0".to_string(), "a; b".to_string()),
+                ("id".to_string(), "say ".to_string()),
+                ("am".to_string(), "Kd1 Kf1".to_string())
+            ],
+            operations
+        );
+    }
+
+    #[test]
+    fn parse_epd_without_operations() {
+        let (game_state, operations) = parse_epd("4k3/8/8/8/8/8/8/4K3 b - -").unwrap();
+        assert_eq!("4k3/8/8/8/8/8/8/4K3 b - - 0 1", game_state.to_string());
+        assert!(operations.is_empty());
+        assert_eq!(
+            Err(FenError::TooFewFields),
+            parse_epd("4k3/8/8/8/8/8/8/4K3 b -")
+        );
+    }
+
+    #[test]
+    fn epd_opcodes() {
+        let epd = "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\"; ce 300; pv Qg6 fxg6;"
+            .parse::<Epd>()
+            .unwrap();
+        assert_eq!(Some("WAC.001"), epd.id());
+        assert_eq!(Some(300), epd.centipawns());
+        assert_eq!(Ok(vec![Move::new(22, 46, None)]), epd.best_moves());
+        assert_eq!(Ok(vec![]), epd.avoid_moves());
+        assert_eq!(
+            Ok(vec![Move::new(22, 46, None), Move::new(53, 46, None)]),
+            epd.pv()
+        );
+        assert_eq!(None, epd.operation("dm"));
+    }
+
+    #[test]
+    fn epd_multiple_operands_and_quoting() {
+        let epd = "4k3/8/8/8/8/8/8/R3K2R w KQ - am O-O O-O-O; c0 \"a; b\";"
+            .parse::<Epd>()
+            .unwrap();
+        assert_eq!(
+            Ok(vec![Move::new(4, 6, None), Move::new(4, 2, None)]),
+            epd.avoid_moves()
+        );
+        assert_eq!(Some(&["a; b".to_string()][..]), epd.operation("c0"));
+        assert_eq!(
+            "4k3/8/8/8/8/8/8/R3K2R w KQ - am O-O O-O-O; c0 \"a; b\";",
+            epd.to_string()
+        );
+        assert_eq!(Ok(epd.clone()), epd.to_string().parse::<Epd>());
+    }
+
+    #[test]
+    fn epd_invalid_operands() {
+        let epd = "4k3/8/8/8/8/8/8/4K3 w - - bm Qh5; pv Kd1 Kd7 Kd1"
+            .parse::<Epd>()
+            .unwrap();
+        assert_eq!(
+            Err(MoveParseError::Illegal("Qh5".to_string())),
+            epd.best_moves()
+        );
+        assert_eq!(Err(MoveParseError::Illegal("Kd1".to_string())), epd.pv());
+        assert_eq!(
+            Err(FenError::TooFewFields),
+            "4k3/8/8/8/8/8/8/4K3 w -".parse::<Epd>()
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use crate::{
+    squares, Bitboard, Color, Color::*, GameState, Move, Piece, Piece::*, Square, KING_ATTACKS,
+    KNIGHT_ATTACKS,
+};
+
+const PIECE_VALUES: [i32; 6] = [100, 320, 330, 500, 900, 0];
+const SEE_KING_VALUE: i32 = 20_000;
+
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10, -20, -20,  10,  10,   5,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      0,   0,   0,  20,  20,   0,   0,   0,
+      5,   5,  10,  25,  25,  10,   5,   5,
+     10,  10,  20,  30,  30,  20,  10,  10,
+     50,  50,  50,  50,  50,  50,  50,  50,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+impl Piece {
+    pub fn value(&self) -> i32 {
+        PIECE_VALUES[self.index() / 2]
+    }
+}
+
+pub trait Evaluator {
+    fn evaluate(&self, state: &GameState) -> i32;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DefaultEvaluator;
+
+impl Evaluator for DefaultEvaluator {
+    fn evaluate(&self, state: &GameState) -> i32 {
+        state.evaluate()
+    }
+}
+
+impl<F: Fn(&GameState) -> i32> Evaluator for F {
+    fn evaluate(&self, state: &GameState) -> i32 {
+        self(state)
+    }
+}
+
+fn see_value(piece: Piece) -> i32 {
+    match piece {
+        King(_) => SEE_KING_VALUE,
+        _ => piece.value(),
+    }
+}
+
+impl GameState {
+    pub fn piece_count(&self, piece: Piece) -> u32 {
+        self.count(piece)
+    }
+
+    pub fn material_balance(&self) -> i32 {
+        self.bitboards
+            .iter()
+            .enumerate()
+            .map(|(index, bitboard)| {
+                let material = PIECE_VALUES[index / 2] * bitboard.count() as i32;
+                if index % 2 == 0 {
+                    material
+                } else {
+                    -material
+                }
+            })
+            .sum()
+    }
+
+    pub fn evaluate(&self) -> i32 {
+        let mut score = self.material_balance();
+        for (table, white, black) in [
+            (&PAWN_TABLE, Pawn(White), Pawn(Black)),
+            (&KNIGHT_TABLE, Knight(White), Knight(Black)),
+        ] {
+            score += squares(self.bitboards[white.index()])
+                .map(|square| table[square])
+                .sum::<i32>();
+            score -= squares(self.bitboards[black.index()])
+                .map(|square| table[square ^ 56])
+                .sum::<i32>();
+        }
+        match self.active_color {
+            White => score,
+            Black => -score,
+        }
+    }
+
+    pub fn mobility(&self, color: Color) -> i32 {
+        let occupancy = self.all_occupancy();
+        let own = self.occupancy(color);
+        let mut targets = 0;
+        for piece in [
+            Knight(color),
+            Bishop(color),
+            Rook(color),
+            Queen(color),
+            King(color),
+        ] {
+            for from in squares(self.bitboards[piece.index()]) {
+                let attacks = match piece {
+                    Knight(_) => KNIGHT_ATTACKS[from],
+                    Bishop(_) => self.bishop_attacks(from, occupancy),
+                    Rook(_) => self.rook_attacks(from, occupancy),
+                    Queen(_) => self.queen_attacks(from, occupancy),
+                    _ => KING_ATTACKS[from],
+                };
+                targets += (attacks & !own).count() as i32;
+            }
+        }
+        targets
+    }
+
+    pub fn see(&self, mv: Move) -> i32 {
+        let (mut from, to) = (mv.from(), mv.to());
+        let Some(mut attacker) = self.get_piece_on(from) else {
+            return 0;
+        };
+        let mut gain = [0; 32];
+        gain[0] = match self.get_piece_on(to) {
+            Some(captured) => captured.value(),
+            None if matches!(attacker, Pawn(_)) && self.en_passant_target == Some(to as u8) => {
+                Pawn(White).value()
+            }
+            None => 0,
+        };
+        if let Some(promotion) = mv.promotion() {
+            gain[0] += promotion.value() - attacker.value();
+            attacker = promotion;
+        }
+        let mut occupancy = self.all_occupancy();
+        let mut color = attacker.color();
+        let mut depth = 0;
+        loop {
+            depth += 1;
+            gain[depth] = see_value(attacker) - gain[depth - 1];
+            occupancy &= !Bitboard::from_square(from);
+            color = color.opposite();
+            match self.least_valuable_attacker(to, color, occupancy) {
+                Some((piece, square)) if depth + 1 < gain.len() => {
+                    attacker = piece;
+                    from = square;
+                }
+                _ => break,
+            }
+        }
+        while depth > 1 {
+            depth -= 1;
+            gain[depth - 1] = -(-gain[depth - 1]).max(gain[depth]);
+        }
+        gain[0]
+    }
+
+    pub fn control(&self, sq: Square) -> i32 {
+        let square = sq.index();
+        self.attackers_of(square, White).count() as i32
+            - self.attackers_of(square, Black).count() as i32
+    }
+
+    pub fn flip_turn_and_eval(&self) -> i32 {
+        let flipped = self.make_null_move();
+        let threat = flipped
+            .legal_captures()
+            .map(|mv| flipped.see(mv))
+            .max()
+            .unwrap_or(0);
+        flipped.evaluate() + threat.max(0)
+    }
+
+    pub fn legal_captures(&self) -> impl Iterator<Item = Move> + '_ {
+        let color = self.active_color;
+        self.legal_moves_iter().filter(move |mv| {
+            self.get_piece_on(mv.to())
+                .is_some_and(|target| target.color() != color)
+                || (matches!(self.get_piece_on(mv.from()), Some(Pawn(_)))
+                    && self.en_passant_target == Some(mv.to() as u8))
+        })
+    }
+
+    fn least_valuable_attacker(
+        &self,
+        square: usize,
+        color: Color,
+        occupancy: Bitboard,
+    ) -> Option<(Piece, usize)> {
+        let attackers = self.attackers_through(square, color, occupancy);
+        (0..6).find_map(|kind| {
+            let piece = Piece::from_index(kind * 2 + color.index())?;
+            let candidates = attackers & self.bitboards[piece.index()];
+            candidates.first().map(|square| (piece, square))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use crate::{Color::*, GameState, Move, Piece::*, Square};
+
+    #[test]
+    fn piece_counts() {
+        let game_state = GameState::default();
+        assert_eq!(8, game_state.piece_count(Pawn(White)));
+        assert_eq!(2, game_state.piece_count(Knight(Black)));
+        assert_eq!(1, game_state.piece_count(Queen(White)));
+        assert_eq!(1, game_state.piece_count(King(Black)));
+    }
+
+    #[test]
+    fn material_balance() {
+        assert_eq!(0, GameState::default().material_balance());
+        let game_state = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNB1KBNR w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(-900, game_state.material_balance());
+        let game_state = "4k3/8/8/8/8/8/8/RN2K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(820, game_state.material_balance());
+    }
+
+    #[test]
+    fn piece_values() {
+        assert_eq!(100, Pawn(Black).value());
+        assert_eq!(320, Knight(White).value());
+        assert_eq!(900, Queen(Black).value());
+        assert_eq!(0, King(White).value());
+    }
+
+    #[test]
+    fn evaluate_is_side_to_move_relative() {
+        assert_eq!(0, GameState::default().evaluate());
+        let game_state = "rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(900, game_state.evaluate());
+        let game_state = "rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(-900, game_state.evaluate());
+        let after_e4 = GameState::default()
+            .play_move(Move::new(12, 28, None))
+            .unwrap();
+        assert_eq!(-40, after_e4.evaluate());
+    }
+
+    #[test]
+    fn see_losing_capture() {
+        let game_state = "4k3/8/3p4/4p3/8/8/8/4Q2K w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(-800, game_state.see(Move::new(4, 36, None)));
+        let game_state = "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(-220, game_state.see(Move::new(19, 36, None)));
+    }
+
+    #[test]
+    fn see_winning_capture() {
+        let game_state = "4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(900, game_state.see(Move::new(28, 35, None)));
+        let game_state = "4k3/8/2p5/3q4/4P3/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(800, game_state.see(Move::new(28, 35, None)));
+        let game_state = "1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(100, game_state.see(Move::new(4, 36, None)));
+        let game_state = "3rk3/2P5/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(400, game_state.see(Move::new(50, 59, Some(Queen(White)))));
+        let game_state = "3r3k/2P5/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(1300, game_state.see(Move::new(50, 59, Some(Queen(White)))));
+    }
+
+    #[test]
+    fn control() {
+        let game_state = GameState::default();
+        for center in ["d4", "e4", "d5", "e5"] {
+            let square = Square::from_algebraic(center).unwrap();
+            assert_eq!(0, game_state.control(square), "{center}");
+        }
+        let control = |square| game_state.control(Square::from_algebraic(square).unwrap());
+        assert_eq!(2, control("d3"));
+        assert_eq!(3, control("f3"));
+        assert_eq!(-3, control("f6"));
+        let after_e4_d5 = game_state.play_moves(&["e4", "d5"]).unwrap();
+        let control = |square| after_e4_d5.control(Square::from_algebraic(square).unwrap());
+        assert_eq!(0, control("d5"));
+        assert_eq!(-1, control("e4"));
+        assert_eq!(0, control("c4"));
+        assert_eq!(2, control("d3"));
+    }
+
+    #[test]
+    fn flip_turn_and_eval_counts_threats() {
+        let hanging = "4k3/8/8/R2q4/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let safe = "4k3/8/8/3q4/8/8/8/R3K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(400, safe.flip_turn_and_eval());
+        assert_eq!(900, hanging.flip_turn_and_eval());
+        assert_eq!(
+            vec![Move::new(35, 32, None)],
+            hanging
+                .make_null_move()
+                .legal_captures()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, GameState::default().flip_turn_and_eval());
+    }
+
+    #[test]
+    fn mobility() {
+        let game_state = GameState::default();
+        assert_eq!(4, game_state.mobility(White));
+        assert_eq!(game_state.mobility(White), game_state.mobility(Black));
+        let after_e3 = game_state.play_move(Move::new(12, 20, None)).unwrap();
+        // Bf1 gains five squares, Qd1 four, and Ng1 and Ke1 both reach e2.
+        assert_eq!(15, after_e3.mobility(White));
+        assert_eq!(4, after_e3.mobility(Black));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{GameState, Move, Piece::*, RANKS};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MoveFlags {
+    pub is_capture: bool,
+    pub is_en_passant: bool,
+    pub is_castle: bool,
+    pub is_promotion: bool,
+    pub gives_check: bool,
+}
+
+impl GameState {
+    pub fn move_flags(&self, from: usize, to: usize) -> MoveFlags {
+        let Some(piece) = self.get_piece_on(from) else {
+            return MoveFlags::default();
+        };
+        let is_pawn = matches!(piece, Pawn(_));
+        let is_castle = self.castling_for(piece, from, to).is_some();
+        let is_en_passant = is_pawn
+            && from % 8 != to % 8
+            && self.en_passant_target == Some(to as u8)
+            && self.get_piece_on(to).is_none();
+        let is_capture = is_en_passant
+            || (!is_castle
+                && self
+                    .get_piece_on(to)
+                    .is_some_and(|target| target.color() != piece.color()));
+        let gives_check = self
+            .play_move(Move::new(from, to, None))
+            .is_ok_and(|next| next.is_in_check(next.active_color));
+        MoveFlags {
+            is_capture,
+            is_en_passant,
+            is_castle,
+            is_promotion: is_pawn && (RANKS[0] | RANKS[7]).contains(to),
+            gives_check,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{GameState, MoveFlags};
+
+    #[test]
+    fn capture_flags() {
+        let game_state = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            MoveFlags {
+                is_capture: true,
+                ..MoveFlags::default()
+            },
+            game_state.move_flags(28, 35)
+        );
+        assert_eq!(MoveFlags::default(), game_state.move_flags(28, 36));
+    }
+
+    #[test]
+    fn en_passant_flags() {
+        let game_state = "4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 2"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            MoveFlags {
+                is_capture: true,
+                is_en_passant: true,
+                ..MoveFlags::default()
+            },
+            game_state.move_flags(35, 44)
+        );
+    }
+
+    #[test]
+    fn check_castle_and_promotion_flags() {
+        let game_state = "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(game_state.move_flags(0, 56).gives_check);
+        assert!(!game_state.move_flags(0, 8).gives_check);
+        let game_state = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let castle = game_state.move_flags(4, 6);
+        assert!(castle.is_castle && !castle.is_capture);
+        let game_state = "3qk3/2P5/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            MoveFlags {
+                is_capture: true,
+                is_promotion: true,
+                gives_check: true,
+                ..MoveFlags::default()
+            },
+            game_state.move_flags(50, 59)
+        );
+    }
+}
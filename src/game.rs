@@ -0,0 +1,279 @@
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::{GameState, Move, UndoInfo};
+
+#[derive(Clone, Debug)]
+pub struct Game {
+    start: GameState,
+    position: GameState,
+    moves: Vec<Move>,
+    undos: Vec<UndoInfo>,
+    redos: Vec<Move>,
+    history: Vec<u64>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new(GameState::default())
+    }
+}
+
+impl Game {
+    pub fn new(position: GameState) -> Self {
+        let history = vec![position.zobrist_hash()];
+        Game {
+            start: position.clone(),
+            position,
+            moves: Vec::new(),
+            undos: Vec::new(),
+            redos: Vec::new(),
+            history,
+        }
+    }
+
+    pub fn start(&self) -> &GameState {
+        &self.start
+    }
+
+    pub fn position(&self) -> &GameState {
+        &self.position
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn positions(&self) -> Vec<GameState> {
+        let mut positions = vec![self.start.clone()];
+        let mut position = self.start.clone();
+        for &mv in &self.moves {
+            position
+                .make_move(mv)
+                .expect("recorded moves can be replayed");
+            positions.push(position.clone());
+        }
+        positions
+    }
+
+    pub fn make_move(&mut self, mv: Move) -> Result<(), String> {
+        self.play(mv)?;
+        self.redos.clear();
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Option<Move> {
+        let mv = self.moves.pop()?;
+        let undo = self.undos.pop().expect("every move has its undo info");
+        self.position.unmake_move(mv, undo);
+        self.history.pop();
+        self.redos.push(mv);
+        Some(mv)
+    }
+
+    pub fn redo(&mut self) -> Option<Move> {
+        let mv = self.redos.pop()?;
+        self.play(mv).expect("undone moves can be replayed");
+        Some(mv)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.moves.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redos.is_empty()
+    }
+
+    fn play(&mut self, mv: Move) -> Result<(), String> {
+        let undo = self.position.make_move(mv)?;
+        self.moves.push(mv);
+        self.undos.push(undo);
+        self.history.push(self.position.zobrist_hash());
+        Ok(())
+    }
+
+    // Positions before the last capture or pawn move can never repeat.
+    fn repetitions(&self) -> usize {
+        let current = self.position.zobrist_hash();
+        self.history
+            .iter()
+            .rev()
+            .take(self.position.half_move_clock as usize + 1)
+            .filter(|&&hash| hash == current)
+            .count()
+    }
+
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetitions() >= 3
+    }
+
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.repetitions() >= 5
+    }
+
+    pub fn is_fifty_move_draw(&self) -> bool {
+        self.position.is_fifty_move_draw()
+    }
+
+    pub fn is_seventy_five_move_draw(&self) -> bool {
+        self.position.half_move_clock >= 150 && !self.position.is_checkmate()
+    }
+
+    pub fn is_automatic_draw(&self) -> bool {
+        self.is_fivefold_repetition() || self.is_seventy_five_move_draw()
+    }
+
+    pub fn is_draw(&self) -> bool {
+        self.is_threefold_repetition() || self.position.is_draw_by_rule()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use crate::{Game, GameState, Move};
+
+    #[test]
+    fn threefold_repetition() {
+        let mut game = Game::default();
+        for _ in 0..2 {
+            assert!(!game.is_threefold_repetition());
+            for (from, to) in [(6, 21), (62, 45), (21, 6), (45, 62)] {
+                game.make_move(Move::new(from, to, None)).unwrap();
+            }
+        }
+        assert!(game.is_threefold_repetition());
+        assert!(game.is_draw());
+    }
+
+    #[test]
+    fn threefold_repetition_reset_by_pawn_move() {
+        let mut game = Game::default();
+        for (from, to) in [(6, 21), (62, 45), (21, 6), (45, 62), (12, 20), (52, 44)] {
+            game.make_move(Move::new(from, to, None)).unwrap();
+        }
+        for _ in 0..2 {
+            for (from, to) in [(6, 21), (62, 45), (21, 6), (45, 62)] {
+                assert!(!game.is_threefold_repetition());
+                game.make_move(Move::new(from, to, None)).unwrap();
+            }
+        }
+        assert!(game.is_threefold_repetition());
+    }
+
+    #[test]
+    fn threefold_repetition_ignores_dangling_en_passant() {
+        let mut game = Game::new(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+                .parse::<GameState>()
+                .unwrap(),
+        );
+        for _ in 0..2 {
+            assert!(!game.is_threefold_repetition());
+            for (from, to) in [(62, 45), (6, 21), (45, 62), (21, 6)] {
+                game.make_move(Move::new(from, to, None)).unwrap();
+            }
+        }
+        assert!(game.is_threefold_repetition());
+    }
+
+    #[test]
+    fn fivefold_repetition_is_automatic() {
+        let mut game = Game::default();
+        for _ in 0..4 {
+            assert!(!game.is_automatic_draw());
+            for (from, to) in [(6, 21), (62, 45), (21, 6), (45, 62)] {
+                game.make_move(Move::new(from, to, None)).unwrap();
+            }
+        }
+        assert!(game.is_fivefold_repetition());
+        assert!(game.is_automatic_draw());
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut game = Game::default();
+        assert_eq!(None, game.undo());
+        let moves =
+            ["e2e4", "d7d5", "e4d5", "d8d5", "b1c3"].map(|uci| uci.parse::<Move>().unwrap());
+        let positions: Vec<GameState> = moves
+            .iter()
+            .map(|&mv| {
+                game.make_move(mv).unwrap();
+                game.position().clone()
+            })
+            .collect();
+        assert_eq!(Some(moves[4]), game.undo());
+        assert_eq!(Some(moves[3]), game.undo());
+        assert_eq!(&positions[2], game.position());
+        assert_eq!(&moves[..3], game.moves());
+        assert!(game.can_redo());
+        assert_eq!(Some(moves[3]), game.redo());
+        assert_eq!(&positions[3], game.position());
+        while game.undo().is_some() {}
+        assert_eq!(game.start(), game.position());
+        assert!(!game.can_undo());
+        for (index, &mv) in moves.iter().enumerate() {
+            assert_eq!(Some(mv), game.redo());
+            assert_eq!(&positions[index], game.position());
+        }
+        assert_eq!(None, game.redo());
+    }
+
+    #[test]
+    fn new_move_clears_redo() {
+        let mut game = Game::default();
+        game.make_move(Move::new(12, 28, None)).unwrap();
+        game.undo();
+        game.make_move(Move::new(11, 27, None)).unwrap();
+        assert!(!game.can_redo());
+        assert_eq!(None, game.redo());
+        assert_eq!(&[Move::new(11, 27, None)], game.moves());
+    }
+
+    #[test]
+    fn undo_restores_repetition_history() {
+        let mut game = Game::default();
+        for _ in 0..2 {
+            for (from, to) in [(6, 21), (62, 45), (21, 6), (45, 62)] {
+                game.make_move(Move::new(from, to, None)).unwrap();
+            }
+        }
+        assert!(game.is_threefold_repetition());
+        game.undo();
+        assert!(!game.is_threefold_repetition());
+        game.redo();
+        assert!(game.is_threefold_repetition());
+        game.make_move(Move::new(12, 28, None)).unwrap();
+        game.undo();
+        assert!(game.is_threefold_repetition());
+    }
+
+    #[test]
+    fn move_rule_draws() {
+        let game = Game::new(
+            "4k3/8/8/8/8/8/8/R3K3 w - - 99 80"
+                .parse::<GameState>()
+                .unwrap(),
+        );
+        assert!(!game.is_fifty_move_draw());
+        let mut game = Game::new(
+            "4k3/8/8/8/8/8/8/R3K3 w - - 100 80"
+                .parse::<GameState>()
+                .unwrap(),
+        );
+        assert!(game.is_fifty_move_draw());
+        assert!(!game.is_automatic_draw());
+        let mut game_75 = Game::new(
+            "4k3/8/8/8/8/8/8/R3K3 w - - 149 80"
+                .parse::<GameState>()
+                .unwrap(),
+        );
+        game_75.make_move(Move::new(0, 8, None)).unwrap();
+        assert!(game_75.is_seventy_five_move_draw());
+        assert!(game_75.is_automatic_draw());
+        game.make_move(Move::new(0, 56, None)).unwrap();
+        assert!(game.is_fifty_move_draw());
+    }
+}
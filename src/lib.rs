@@ -1,57 +1,201 @@
-use std::{fmt::Display, str::FromStr};
-
-type Bitboard = u64;
-
-const FILES: [u64; 8] = [
-    0x8080808080808080,
-    0x4040404040404040,
-    0x2020202020202020,
-    0x1010101010101010,
-    0x0808080808080808,
-    0x0404040404040404,
-    0x0202020202020202,
-    0x0101010101010101,
+#![no_std]
+
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(test, macro_use)]
+extern crate std;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Display, str::FromStr};
+
+mod attacks;
+mod bitboard;
+mod book;
+mod builder;
+mod chess960;
+mod epd;
+mod eval;
+mod flags;
+mod game;
+#[cfg(feature = "std")]
+mod magic;
+mod move_list;
+mod moves;
+mod pgn;
+#[cfg(feature = "rand")]
+mod random;
+mod render;
+mod san;
+pub mod search;
+#[cfg(feature = "serde")]
+mod serialize;
+mod square;
+mod symmetry;
+#[cfg(feature = "syzygy")]
+mod syzygy;
+pub mod tt;
+mod uci;
+mod zobrist;
+
+use attacks::{KING_ATTACKS, KNIGHT_ATTACKS, PAWN_ATTACKS};
+
+use bitboard::occupancy_of;
+pub use bitboard::{bit, is_set, squares, Bitboard, BitboardIter};
+pub use book::{Book, BookEntry, BookError, BookMove, POLYGLOT_RANDOMS, POLYGLOT_RANDOM_COUNT};
+pub use builder::GameStateBuilder;
+pub use epd::{parse_epd, Epd};
+pub use eval::{DefaultEvaluator, Evaluator};
+pub use flags::MoveFlags;
+pub use game::Game;
+pub use move_list::{MoveList, MoveListIntoIter};
+pub use moves::{Move, MoveParseError};
+pub use pgn::{parse_pgn, parse_pgn_movetext, PgnError, PgnGame, PgnVariation};
+#[cfg(feature = "serde")]
+pub use serialize::bitboards as serde_bitboards;
+pub use square::{File, Rank, Square};
+#[cfg(feature = "syzygy")]
+pub use syzygy::{SyzygyError, Tablebases, Wdl};
+pub use uci::{uci_string, UciEngine};
+
+const FILES: [Bitboard; 8] = [
+    Bitboard(0x0101010101010101),
+    Bitboard(0x0202020202020202),
+    Bitboard(0x0404040404040404),
+    Bitboard(0x0808080808080808),
+    Bitboard(0x1010101010101010),
+    Bitboard(0x2020202020202020),
+    Bitboard(0x4040404040404040),
+    Bitboard(0x8080808080808080),
 ];
 
-const RANKS: [u64; 8] = [
-    0x00000000000000FF,
-    0x000000000000FF00,
-    0x0000000000FF0000,
-    0x00000000FF000000,
-    0x000000FF00000000,
-    0x0000FF0000000000,
-    0x00FF000000000000,
-    0xFF00000000000000,
+const RANKS: [Bitboard; 8] = [
+    Bitboard(0x00000000000000FF),
+    Bitboard(0x000000000000FF00),
+    Bitboard(0x0000000000FF0000),
+    Bitboard(0x00000000FF000000),
+    Bitboard(0x000000FF00000000),
+    Bitboard(0x0000FF0000000000),
+    Bitboard(0x00FF000000000000),
+    Bitboard(0xFF00000000000000),
 ];
 
+const LIGHT_SQUARES: Bitboard = Bitboard(0x55AA55AA55AA55AA);
+
 const RANK_MATRIX: [&str; 8] = ["a", "b", "c", "d", "e", "f", "g", "h"];
 
-fn square_number_from_str(str: &str) -> Option<u8> {
-    Some(
-        str.chars().nth(0)?.to_digit(16)? as u8 - 10 * 8 + str.chars().nth(1)?.to_digit(10)? as u8
-            - 1,
-    )
+const ROOK_DIRECTIONS: [(i8, Bitboard); 4] = [
+    (8, Bitboard::EMPTY),
+    (-8, Bitboard::EMPTY),
+    (1, FILES[0]),
+    (-1, FILES[7]),
+];
+
+const BISHOP_DIRECTIONS: [(i8, Bitboard); 4] =
+    [(9, FILES[0]), (7, FILES[7]), (-7, FILES[0]), (-9, FILES[7])];
+
+const CLASSIC_ROOK_FILES: [u8; 4] = [7, 0, 7, 0];
+
+struct Castling {
+    right: usize,
+    king_from: usize,
+    king_to: usize,
+    rook_from: usize,
+    rook_to: usize,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Color {
+impl Castling {
+    fn new(right: usize, king_from: usize, rook_file: u8) -> Self {
+        let back_rank = if right < 2 { 0 } else { 56 };
+        let kingside = right.is_multiple_of(2);
+        Castling {
+            right,
+            king_from,
+            king_to: back_rank + if kingside { 6 } else { 2 },
+            rook_from: back_rank + rook_file as usize,
+            rook_to: back_rank + if kingside { 5 } else { 3 },
+        }
+    }
+
+    fn is_classic(&self) -> bool {
+        self.king_from % 8 == 4 && self.rook_from % 8 == CLASSIC_ROOK_FILES[self.right] as usize
+    }
+
+    fn king_target(&self) -> usize {
+        if self.is_classic() {
+            self.king_to
+        } else {
+            self.rook_from
+        }
+    }
+
+    fn between(&self) -> Bitboard {
+        let squares = [self.king_from, self.king_to, self.rook_from, self.rook_to];
+        let low = squares.iter().min().copied().unwrap_or(0);
+        let high = squares.iter().max().copied().unwrap_or(0);
+        let span = Bitboard((u64::MAX >> (63 - high)) & (u64::MAX << low));
+        span & !Bitboard::from_square(self.king_from) & !Bitboard::from_square(self.rook_from)
+    }
+}
+
+fn outermost_rook_file(bitboards: &[Bitboard; 12], right: usize) -> Option<u8> {
+    let color = Color::ALL[right / 2];
+    let back_rank = if color == White { 0 } else { 56 };
+    let king = bitboards[King(color).index()].first()?;
+    if king / 8 != back_rank / 8 {
+        return None;
+    }
+    let rooks = bitboards[Rook(color).index()];
+    let is_rook = |file: &usize| rooks.contains(back_rank + file);
+    if right.is_multiple_of(2) {
+        (king % 8 + 1..8).rev().find(is_rook)
+    } else {
+        (0..king % 8).find(is_rook)
+    }
+    .map(|file| file as u8)
+}
+
+fn parse_square(str: &str) -> Option<usize> {
+    Square::from_algebraic(str).map(|square| square.index())
+}
+
+fn square_name(square: usize) -> String {
+    Square::from_index(square)
+        .map(|square| square.to_string())
+        .unwrap_or_default()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
     Black,
     White,
 }
 
 impl Color {
-    fn index(&self) -> usize {
+    pub const ALL: [Color; 2] = [White, Black];
+
+    pub fn index(&self) -> usize {
         match self {
             Black => 1,
             White => 0,
         }
     }
+
+    pub fn opposite(&self) -> Color {
+        match self {
+            Black => White,
+            White => Black,
+        }
+    }
 }
 
 use Color::*;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Piece {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Piece {
     Pawn(Color),
     Knight(Color),
     Bishop(Color),
@@ -63,7 +207,7 @@ enum Piece {
 impl Piece {
     fn index(&self) -> usize {
         match self {
-            Pawn(color) => 0 + color.index(),
+            Pawn(color) => color.index(),
             Knight(color) => 2 + color.index(),
             Bishop(color) => 4 + color.index(),
             Rook(color) => 6 + color.index(),
@@ -72,6 +216,30 @@ impl Piece {
         }
     }
 
+    pub fn color(&self) -> Color {
+        match self {
+            Pawn(color) | Knight(color) | Bishop(color) | Rook(color) | Queen(color)
+            | King(color) => *color,
+        }
+    }
+
+    pub fn unicode(&self) -> char {
+        match self {
+            Pawn(White) => '♙',
+            Knight(White) => '♘',
+            Bishop(White) => '♗',
+            Rook(White) => '♖',
+            Queen(White) => '♕',
+            King(White) => '♔',
+            Pawn(Black) => '♟',
+            Knight(Black) => '♞',
+            Bishop(Black) => '♝',
+            Rook(Black) => '♜',
+            Queen(Black) => '♛',
+            King(Black) => '♚',
+        }
+    }
+
     pub fn fen(&self) -> String {
         match self {
             Pawn(color) => {
@@ -158,14 +326,38 @@ impl Piece {
 
 use Piece::*;
 
-#[derive(Debug, PartialEq)]
-struct GameState {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameResult {
+    Ongoing,
+    Checkmate(Color),
+    Stalemate,
+    Draw,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UndoInfo {
+    moved: Piece,
+    captured: Option<Piece>,
+    castling_rights: [bool; 4],
+    en_passant_target: Option<u8>,
+    half_move_clock: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NullMoveUndo {
+    en_passant_target: Option<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameState {
     bitboards: [Bitboard; 12],
+    occupancy: [Bitboard; 2],
     active_color: Color,
     castling_rights: [bool; 4],
+    castling_rook_files: [u8; 4],
     en_passant_target: Option<u8>,
-    half_move_clock: u8,
-    full_move_number: u8,
+    half_move_clock: u16,
+    full_move_number: u16,
 }
 
 impl Default for GameState {
@@ -173,21 +365,23 @@ impl Default for GameState {
         let bitboards = [
             RANKS[1],
             RANKS[6],
-            0x0000000000000042,
-            0x4200000000000000,
-            0x0000000000000024,
-            0x2400000000000000,
-            0x0000000000000081,
-            0x8100000000000000,
-            0x0000000000000010,
-            0x1000000000000000,
-            0x0000000000000008,
-            0x0800000000000000,
+            Bitboard(0x0000000000000042),
+            Bitboard(0x4200000000000000),
+            Bitboard(0x0000000000000024),
+            Bitboard(0x2400000000000000),
+            Bitboard(0x0000000000000081),
+            Bitboard(0x8100000000000000),
+            Bitboard(0x0000000000000008),
+            Bitboard(0x0800000000000000),
+            Bitboard(0x0000000000000010),
+            Bitboard(0x1000000000000000),
         ];
         GameState {
+            occupancy: occupancy_of(&bitboards),
             bitboards,
             active_color: White,
             castling_rights: [true; 4],
+            castling_rook_files: CLASSIC_ROOK_FILES,
             en_passant_target: None,
             half_move_clock: 0,
             full_move_number: 1,
@@ -196,7 +390,7 @@ impl Default for GameState {
 }
 
 impl Display for GameState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let board_state = self.get_board_state();
         let mut position: Vec<String> = Vec::new();
         for rank in (0..8).rev() {
@@ -224,27 +418,9 @@ impl Display for GameState {
             Black => write!(f, "b ")?,
             White => write!(f, "w ")?,
         };
-        let mut buffer = "".to_string();
-        for (i, can_castle) in self.castling_rights.iter().enumerate() {
-            let mut char = "".to_string();
-            if i % 2 == 0 && *can_castle {
-                char = "K".to_string();
-            } else if *can_castle {
-                char = "Q".to_string();
-            }
-            if i >= 2 {
-                char = char.to_lowercase();
-            }
-            buffer.push_str(&char);
-        }
-        if buffer == "" {
-            buffer = "-".to_string();
-        }
-        write!(f, "{} ", buffer)?;
+        write!(f, "{} ", self.castling_fen())?;
         if let Some(en_passant_target) = self.en_passant_target {
-            let file = en_passant_target % 8 + 1;
-            let rank = RANK_MATRIX[en_passant_target as usize / 8];
-            write!(f, "{}{} ", rank, file)?;
+            write!(f, "{} ", square_name(en_passant_target as usize))?;
         } else {
             write!(f, "- ")?;
         }
@@ -253,59 +429,178 @@ impl Display for GameState {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum FenError {
+    TooFewFields,
+    MissingField(usize),
+    BadRankCount(usize),
+    BadRankLength { rank: usize, squares: usize },
+    BadPiece(char),
+    MissingKing(Color),
+    ExtraKing(Color),
+    BadActiveColor(String),
+    BadCastling(String),
+    BadEnPassant(String),
+    BadNumber(String),
+    OpponentInCheck(Color),
+    PawnOnBackRank,
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FenError::TooFewFields => write!(f, "not enough fields"),
+            FenError::MissingField(index) => write!(f, "field {index} is empty"),
+            FenError::BadRankCount(ranks) => write!(f, "board describes {ranks} ranks"),
+            FenError::BadRankLength { rank, squares } => {
+                write!(f, "rank {rank} describes {squares} squares")
+            }
+            FenError::BadPiece(char) => write!(f, "invalid piece character '{char}'"),
+            FenError::MissingKing(color) => {
+                write!(f, "no {} king", format!("{color:?}").to_lowercase())
+            }
+            FenError::ExtraKing(color) => {
+                write!(
+                    f,
+                    "more than one {} king",
+                    format!("{color:?}").to_lowercase()
+                )
+            }
+            FenError::BadActiveColor(field) => write!(f, "invalid active color {field}"),
+            FenError::BadCastling(field) => write!(f, "invalid castling rights {field}"),
+            FenError::BadEnPassant(field) => write!(f, "invalid en passant target {field}"),
+            FenError::BadNumber(message) => write!(f, "{message}"),
+            FenError::OpponentInCheck(color) => {
+                write!(
+                    f,
+                    "{} is in check but not to move",
+                    format!("{color:?}").to_lowercase()
+                )
+            }
+            FenError::PawnOnBackRank => write!(f, "pawns on the first or last rank"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FenError {}
+
 impl FromStr for GameState {
-    type Err = String;
+    type Err = FenError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let splits: Vec<&str> = s.split(" ").collect();
-        if splits.len() < 6 {
-            return Err("Not enough fields for ".to_string());
+        if splits.len() < 4 {
+            return Err(FenError::TooFewFields);
+        }
+        if let Some(index) = splits.iter().position(|field| field.is_empty()) {
+            return Err(FenError::MissingField(index));
         }
-        let mut bitboards = [0x0; 12];
-        let board: &str = splits.get(0).ok_or("No board state")?;
-        for (rank, file_val) in board.split("/").enumerate() {
+        let mut bitboards = [Bitboard::EMPTY; 12];
+        let ranks: Vec<&str> = splits[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err(FenError::BadRankCount(ranks.len()));
+        }
+        for (rank, file_val) in ranks.iter().enumerate() {
             let mut file = 0;
             for piece in file_val.chars() {
                 if let Some(piece) = Piece::from_char(piece) {
-                    bitboards[piece.index()] |= 1 << ((7 - rank) * 8 + (7 - file));
+                    if file < 8 {
+                        bitboards[piece.index()] |= Bitboard::from_square((7 - rank) * 8 + file);
+                    }
                     file += 1;
-                } else if piece.is_numeric() {
-                    file += piece.to_string().parse::<usize>().map_err(|parse_int_error| format!("Could not parse character a number: {parse_int_error}"))?;
+                } else if let Some(blanks @ 1..=8) = piece.to_digit(10) {
+                    file += blanks as usize;
+                } else {
+                    return Err(FenError::BadPiece(piece));
                 }
             }
+            if file != 8 {
+                return Err(FenError::BadRankLength {
+                    rank: 8 - rank,
+                    squares: file,
+                });
+            }
         }
-        let active_color = if *splits.get(1).ok_or("No color")? == "w" {
-            White
-        } else {
-            Black
+        for color in Color::ALL {
+            match bitboards[King(color).index()].count() {
+                0 => return Err(FenError::MissingKing(color)),
+                1 => {}
+                _ => return Err(FenError::ExtraKing(color)),
+            }
+        }
+        let active_color = match splits[1] {
+            "w" => White,
+            "b" => Black,
+            field => return Err(FenError::BadActiveColor(field.to_string())),
         };
         let mut castling_rights = [false; 4];
-        let castling_rights_str = splits.get(2).ok_or("No castling rights")?;
-        if castling_rights_str.contains("K") {
-            castling_rights[0] = true;
-        }
-        if castling_rights_str.contains("k") {
-            castling_rights[2] = true;
-        }
-        if castling_rights_str.contains("Q") {
-            castling_rights[1] = true;
-        }
-        if castling_rights_str.contains("q") {
-            castling_rights[3] = true;
+        let mut castling_rook_files = CLASSIC_ROOK_FILES;
+        let castling_rights_str = splits[2];
+        let bad_castling = || FenError::BadCastling(castling_rights_str.to_string());
+        if castling_rights_str != "-" {
+            for char in castling_rights_str.chars() {
+                let (right, rook_file) = match char {
+                    'K' | 'Q' | 'k' | 'q' => {
+                        let right = "KQkq".find(char).unwrap_or(0);
+                        let rook_file = outermost_rook_file(&bitboards, right)
+                            .unwrap_or(CLASSIC_ROOK_FILES[right]);
+                        (right, rook_file)
+                    }
+                    'A'..='H' | 'a'..='h' => {
+                        let (color, rook_file) = if char.is_ascii_uppercase() {
+                            (White, char as u8 - b'A')
+                        } else {
+                            (Black, char as u8 - b'a')
+                        };
+                        let king = bitboards[King(color).index()].first().unwrap_or(64);
+                        let back_rank = if color == White { 0 } else { 7 };
+                        if king / 8 != back_rank || king % 8 == rook_file as usize {
+                            return Err(bad_castling());
+                        }
+                        let queenside = usize::from((rook_file as usize) < king % 8);
+                        (color.index() * 2 + queenside, rook_file)
+                    }
+                    _ => return Err(bad_castling()),
+                };
+                if castling_rights[right] {
+                    return Err(bad_castling());
+                }
+                castling_rights[right] = true;
+                castling_rook_files[right] = rook_file;
+            }
         }
-        let en_passant_target_str = *splits.get(3).ok_or("No en passant target")?;
+        let en_passant_target_str = splits[3];
         let en_passant_target = if en_passant_target_str == "-" {
             None
         } else {
-            square_number_from_str(en_passant_target_str)
+            Some(
+                parse_square(en_passant_target_str)
+                    .ok_or(FenError::BadEnPassant(en_passant_target_str.to_string()))?
+                    as u8,
+            )
         };
         Ok(GameState {
+            occupancy: occupancy_of(&bitboards),
             bitboards,
             active_color,
             castling_rights,
+            castling_rook_files,
             en_passant_target,
-            half_move_clock: splits.get(4).ok_or("No half move clock")?.parse::<u8>().map_err(|parse_int_error| format!("Could not parse half move clock: {parse_int_error}"))?,
-            full_move_number: splits.get(5).ok_or("No full move number")?.parse::<u8>().map_err(|parse_int_error| format!("Could not parse full move number: {parse_int_error}"))?,
+            half_move_clock: splits.get(4).map_or(Ok(0), |field| {
+                field.parse::<u16>().map_err(|parse_int_error| {
+                    FenError::BadNumber(format!(
+                        "Could not parse half move clock: {parse_int_error}"
+                    ))
+                })
+            })?,
+            full_move_number: splits.get(5).map_or(Ok(1), |field| {
+                field.parse::<u16>().map_err(|parse_int_error| {
+                    FenError::BadNumber(format!(
+                        "Could not parse full move number: {parse_int_error}"
+                    ))
+                })
+            })?,
         })
     }
 }
@@ -316,50 +611,2087 @@ impl GameState {
     }
 
     pub fn get_piece_at(&self, rank: usize, file: usize) -> Option<Piece> {
-        let mask = RANKS[rank] & FILES[file];
-        let mut piece = None;
-        for (index, val) in self.bitboards.iter().enumerate() {
-            if mask & val != 0 {
-                piece = Piece::from_index(index);
-                break;
+        let square = rank * 8 + file;
+        let color = Color::ALL
+            .into_iter()
+            .find(|color| self.occupancy[color.index()].contains(square))?;
+        (color.index()..12)
+            .step_by(2)
+            .find(|&index| self.bitboards[index].contains(square))
+            .and_then(Piece::from_index)
+    }
+
+    pub fn get_piece_on_square(&self, square: Square) -> Option<Piece> {
+        self.get_piece_on(square.index())
+    }
+
+    pub fn get_en_passant_square(&self) -> Option<Square> {
+        self.en_passant_target
+            .and_then(|target| Square::from_index(target as usize))
+    }
+
+    pub fn get_piece_on(&self, square: usize) -> Option<Piece> {
+        self.get_piece_at(square / 8, square % 8)
+    }
+
+    pub fn play_move(&self, mv: Move) -> Result<GameState, String> {
+        let mut next = self.clone();
+        next.make_move(mv)?;
+        Ok(next)
+    }
+
+    pub fn play_moves(&self, moves: &[&str]) -> Result<GameState, String> {
+        let mut game_state = self.clone();
+        for (index, token) in moves.iter().enumerate() {
+            let mv = match game_state.parse_uci(token) {
+                Ok(mv) if game_state.legal_moves_iter().any(|legal| legal == mv) => Ok(mv),
+                _ => game_state.parse_san(token),
             }
+            .map_err(|err| format!("Move {index} ({token}): {err}"))?;
+            game_state.make_move(mv)?;
         }
-        piece
+        Ok(game_state)
     }
 
-    pub fn get_board_state(&self) -> [[Option<Piece>; 8]; 8] {
-        let mut board = [[None; 8]; 8];
-        for rank in 0..8 {
-            for file in 0..8 {
-                board[rank][file] = self.get_piece_at(rank, file);
+    pub fn make_move(&mut self, mv: Move) -> Result<UndoInfo, String> {
+        let (from, to, promotion) = (mv.from(), mv.to(), mv.promotion());
+        let piece = self
+            .get_piece_on(from)
+            .ok_or(format!("No piece on square {from}"))?;
+        let color = self.active_color;
+        if piece.color() != color {
+            return Err(format!(
+                "{piece:?} on square {from} does not belong to {color:?}"
+            ));
+        }
+        let promotes = matches!(piece, Pawn(_)) && (RANKS[0] | RANKS[7]).contains(to);
+        let promotion = match promotion {
+            None if promotes => Some(Queen(color)),
+            None => None,
+            Some(_) if !promotes => {
+                return Err(format!("Move from {from} to {to} is not a promotion"))
+            }
+            Some(promotion) if promotion.color() != color => {
+                return Err(format!("{color:?} cannot promote to {promotion:?}"))
+            }
+            promotion => promotion,
+        };
+        let castling = self.castling_for(piece, from, to).filter(|_| {
+            self.get_piece_on(to)
+                .is_none_or(|target| target == Rook(color))
+        });
+        let mut captured = self.get_piece_on(to).filter(|_| castling.is_none());
+        let undo = UndoInfo {
+            moved: piece,
+            captured,
+            castling_rights: self.castling_rights,
+            en_passant_target: self.en_passant_target,
+            half_move_clock: self.half_move_clock,
+        };
+
+        if let Some(castling) = &castling {
+            self.remove_piece(piece, from);
+            self.remove_piece(Rook(color), castling.rook_from);
+            self.put_piece(piece, castling.king_to);
+            self.put_piece(Rook(color), castling.rook_to);
+        } else {
+            if let Some(captured) = captured {
+                self.remove_piece(captured, to);
             }
+            self.remove_piece(piece, from);
+            self.put_piece(promotion.unwrap_or(piece), to);
         }
-        board
+
+        let is_pawn = matches!(piece, Pawn(_));
+        self.en_passant_target = None;
+        if is_pawn {
+            if Some(to as u8) == undo.en_passant_target {
+                let captured_square = if color == White { to - 8 } else { to + 8 };
+                self.remove_piece(Pawn(color.opposite()), captured_square);
+                captured = Some(Pawn(color.opposite()));
+            }
+            if from.abs_diff(to) == 16 {
+                self.en_passant_target = Some(((from + to) / 2) as u8);
+            }
+        }
+
+        self.revoke_castling_rights(piece, from, to);
+
+        if is_pawn || captured.is_some() {
+            self.half_move_clock = 0;
+        } else {
+            self.half_move_clock += 1;
+        }
+        if color == Black {
+            self.full_move_number += 1;
+        }
+        self.active_color = color.opposite();
+        Ok(UndoInfo { captured, ..undo })
     }
-}
 
-#[cfg(test)]
-mod test {
-    use crate::GameState;
+    pub fn unmake_move(&mut self, mv: Move, undo: UndoInfo) {
+        let (from, to) = (mv.from(), mv.to());
+        let color = undo.moved.color();
+        self.castling_rights = undo.castling_rights;
+        let castling = self
+            .castling_for(undo.moved, from, to)
+            .filter(|_| undo.captured.is_none());
+        if let Some(castling) = castling {
+            self.remove_piece(undo.moved, castling.king_to);
+            self.remove_piece(Rook(color), castling.rook_to);
+            self.put_piece(undo.moved, castling.king_from);
+            self.put_piece(Rook(color), castling.rook_from);
+        } else {
+            self.restore_move(from, to, undo);
+        }
 
-    #[test]
-    fn board_state() {
-        let game_state = GameState::default();
-        assert_eq!(
-            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
-            format!("{game_state}")
-        );
+        if color == Black {
+            self.full_move_number -= 1;
+        }
+        self.active_color = color;
+        self.en_passant_target = undo.en_passant_target;
+        self.half_move_clock = undo.half_move_clock;
     }
 
-    #[test]
-    fn board_from_fen() {
-        let game_state = GameState::default();
-        let default_fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
-        let actual = default_fen.parse::<GameState>();
-        assert!(!actual.is_err());
-        assert_eq!(
-            game_state,
-            actual.unwrap()
-        );
+    pub fn make_null_move(&self) -> GameState {
+        let mut game_state = self.clone();
+        game_state.apply_null_move();
+        game_state
+    }
+
+    // A pass is not a move for the fifty-move rule, so both clocks are left alone.
+    pub fn apply_null_move(&mut self) -> NullMoveUndo {
+        let undo = NullMoveUndo {
+            en_passant_target: self.en_passant_target.take(),
+        };
+        self.active_color = self.active_color.opposite();
+        undo
+    }
+
+    pub fn unmake_null_move(&mut self, undo: NullMoveUndo) {
+        self.active_color = self.active_color.opposite();
+        self.en_passant_target = undo.en_passant_target;
+    }
+
+    fn restore_move(&mut self, from: usize, to: usize, undo: UndoInfo) {
+        let color = undo.moved.color();
+        let to_bit = Bitboard::from_square(to);
+        for bitboard in self.bitboards.iter_mut().skip(color.index()).step_by(2) {
+            *bitboard &= !to_bit;
+        }
+        self.occupancy[color.index()] &= !to_bit;
+        self.put_piece(undo.moved, from);
+        if let Some(captured) = undo.captured {
+            let captured_square = match undo.moved {
+                Pawn(White) if undo.en_passant_target == Some(to as u8) => to - 8,
+                Pawn(Black) if undo.en_passant_target == Some(to as u8) => to + 8,
+                _ => to,
+            };
+            self.put_piece(captured, captured_square);
+        }
+    }
+
+    fn revoke_castling_rights(&mut self, piece: Piece, from: usize, to: usize) {
+        for right in 0..4 {
+            let color = if right < 2 { White } else { Black };
+            let back_rank = if color == White { 0 } else { 56 };
+            let rook_square = back_rank + self.castling_rook_files[right] as usize;
+            if piece == King(color) || from == rook_square || to == rook_square {
+                self.castling_rights[right] = false;
+            }
+        }
+    }
+
+    fn castlings(&self, color: Color, king_from: usize) -> impl Iterator<Item = Castling> + '_ {
+        let back_rank = if color == White { 0 } else { 7 };
+        (color.index() * 2..color.index() * 2 + 2)
+            .filter(move |&right| self.castling_rights[right] && king_from / 8 == back_rank)
+            .map(move |right| Castling::new(right, king_from, self.castling_rook_files[right]))
+    }
+
+    fn castling_for(&self, piece: Piece, from: usize, to: usize) -> Option<Castling> {
+        match piece {
+            King(color) => self
+                .castlings(color, from)
+                .find(|castling| castling.king_target() == to),
+            _ => None,
+        }
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut list = MoveList::new();
+        self.generate_legal_into(&mut list);
+        list.iter().copied().collect()
+    }
+
+    pub fn generate_legal_into(&self, list: &mut MoveList) {
+        list.clear();
+        for mv in self.legal_moves_iter() {
+            list.push(mv);
+        }
+    }
+
+    pub fn legal_move_count(&self) -> usize {
+        self.legal_moves_iter().count()
+    }
+
+    pub fn promotion_moves(&self, from: Square, to: Square) -> Vec<Move> {
+        self.legal_moves_iter()
+            .filter(|mv| {
+                mv.from() == from.index() && mv.to() == to.index() && mv.promotion().is_some()
+            })
+            .collect()
+    }
+
+    pub fn reachable_squares(&self, sq: Square) -> Bitboard {
+        self.legal_moves_iter()
+            .filter(|mv| mv.from() == sq.index())
+            .fold(Bitboard::EMPTY, |reachable, mv| {
+                reachable | Bitboard::from_square(mv.to())
+            })
+    }
+
+    pub fn legal_moves_iter(&self) -> impl Iterator<Item = Move> + '_ {
+        let color = self.active_color;
+        let in_check = self.is_in_check(color);
+        let king = self.bitboards[King(color).index()].first();
+        self.pseudo_legal_moves_iter()
+            .filter(move |&mv| self.is_legal_pseudo_move(mv, king, in_check))
+    }
+
+    // Legality is decided from the occupancy after the move, so the position
+    // itself is never copied or modified.
+    fn is_legal_pseudo_move(&self, mv: Move, king: Option<usize>, in_check: bool) -> bool {
+        let color = self.active_color;
+        let (from, to) = (mv.from(), mv.to());
+        let Some(piece) = self.get_piece_on(from) else {
+            return false;
+        };
+        let occupancy = self.all_occupancy();
+        if let Some(castling) = self.castling_for(piece, from, to) {
+            let low = castling.king_from.min(castling.king_to);
+            let high = castling.king_from.max(castling.king_to);
+            if in_check
+                || (low + 1..high).any(|square| self.is_square_attacked(square, color.opposite()))
+            {
+                return false;
+            }
+            let after = (occupancy
+                & !Bitboard::from_square(castling.king_from)
+                & !Bitboard::from_square(castling.rook_from))
+                | Bitboard::from_square(castling.king_to)
+                | Bitboard::from_square(castling.rook_to);
+            return self
+                .attackers_through(castling.king_to, color.opposite(), after)
+                .is_empty();
+        }
+        let to_bit = Bitboard::from_square(to);
+        let captured = match piece {
+            Pawn(_)
+                if from % 8 != to % 8
+                    && self.en_passant_target == Some(to as u8)
+                    && !occupancy.contains(to) =>
+            {
+                Bitboard::from_square(if color == White { to - 8 } else { to + 8 })
+            }
+            _ => to_bit,
+        };
+        let after = (occupancy & !Bitboard::from_square(from) & !captured) | to_bit;
+        let king = match (piece, king) {
+            (King(_), _) => to,
+            (_, Some(king)) => king,
+            (_, None) => return true,
+        };
+        (self.attackers_through(king, color.opposite(), after) & !to_bit).is_empty()
+    }
+
+    pub fn is_fifty_move_draw(&self) -> bool {
+        self.half_move_clock >= 100
+    }
+
+    pub fn is_insufficient_material(&self) -> bool {
+        let heavy = [Pawn, Rook, Queen]
+            .iter()
+            .flat_map(|piece| [piece(White), piece(Black)])
+            .fold(Bitboard::EMPTY, |all, piece| {
+                all | self.bitboards[piece.index()]
+            });
+        if !heavy.is_empty() {
+            return false;
+        }
+        let knights = self.bitboards[Knight(White).index()] | self.bitboards[Knight(Black).index()];
+        let white_bishops = self.bitboards[Bishop(White).index()];
+        let black_bishops = self.bitboards[Bishop(Black).index()];
+        let minors = knights | white_bishops | black_bishops;
+        match minors.count() {
+            0 | 1 => true,
+            2 if knights.is_empty() && white_bishops.count() == 1 => {
+                (white_bishops & LIGHT_SQUARES).is_empty()
+                    == (black_bishops & LIGHT_SQUARES).is_empty()
+            }
+            _ => false,
+        }
+    }
+
+    // Repetition needs the game history, so it is left to Game::is_draw.
+    pub fn is_draw_by_rule(&self) -> bool {
+        self.is_fifty_move_draw()
+            || self.is_insufficient_material()
+            || (!self.is_in_check(self.active_color) && self.legal_moves_iter().next().is_none())
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check(self.active_color) && self.legal_moves_iter().next().is_none()
+    }
+
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_in_check(self.active_color) && self.legal_moves_iter().next().is_none()
+    }
+
+    pub fn result(&self) -> GameResult {
+        if self.legal_moves_iter().next().is_none() {
+            if self.is_in_check(self.active_color) {
+                GameResult::Checkmate(self.active_color.opposite())
+            } else {
+                GameResult::Stalemate
+            }
+        } else if self.is_fifty_move_draw() || self.is_insufficient_material() {
+            GameResult::Draw
+        } else {
+            GameResult::Ongoing
+        }
+    }
+
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        if depth == 1 {
+            return self.legal_move_count() as u64;
+        }
+        let mut moves = MoveList::new();
+        self.generate_legal_into(&mut moves);
+        let mut scratch = self.clone();
+        let mut nodes = 0;
+        for &mv in &moves {
+            let undo = scratch.make_move(mv).expect("legal moves can be applied");
+            nodes += scratch.perft(depth - 1);
+            scratch.unmake_move(mv, undo);
+        }
+        nodes
+    }
+
+    pub fn perft_divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        self.legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let next = self.play_move(mv).expect("legal moves can be applied");
+                (mv, next.perft(depth.saturating_sub(1)))
+            })
+            .collect()
+    }
+
+    pub fn pseudo_legal_moves(&self) -> Vec<Move> {
+        self.pseudo_legal_moves_iter().collect()
+    }
+
+    // Generated one piece at a time: the targets of a piece are only computed
+    // once the moves of the previous piece have been consumed.
+    fn pseudo_legal_moves_iter(&self) -> impl Iterator<Item = Move> + '_ {
+        let color = self.active_color;
+        let own = self.occupancy(color);
+        let occupancy = own | self.occupancy(color.opposite());
+        squares(own).flat_map(move |from| {
+            let piece = self.get_piece_on(from);
+            let targets = match piece {
+                Some(Pawn(_)) => self.pawn_moves(from),
+                Some(Knight(_)) => self.knight_moves(from),
+                Some(Bishop(_)) => self.bishop_attacks(from, occupancy) & !own,
+                Some(Rook(_)) => self.rook_attacks(from, occupancy) & !own,
+                Some(Queen(_)) => self.queen_attacks(from, occupancy) & !own,
+                Some(King(_)) => self.king_moves(from),
+                None => Bitboard::EMPTY,
+            };
+            let is_pawn = matches!(piece, Some(Pawn(_)));
+            squares(targets).flat_map(move |to| {
+                let (promotions, count) = if is_pawn && (RANKS[0] | RANKS[7]).contains(to) {
+                    (
+                        [Queen(color), Rook(color), Bishop(color), Knight(color)].map(Some),
+                        4,
+                    )
+                } else {
+                    ([None; 4], 1)
+                };
+                let is_castle = matches!(piece, Some(King(_)))
+                    && self.castling_for(King(color), from, to).is_some();
+                let is_en_passant = is_pawn
+                    && from % 8 != to % 8
+                    && self.en_passant_target == Some(to as u8)
+                    && !occupancy.contains(to);
+                let is_capture = !is_castle && self.occupancy(color.opposite()).contains(to);
+                promotions
+                    .into_iter()
+                    .take(count)
+                    .map(move |promotion| match () {
+                        _ if is_castle => Move::new_castle(from, to),
+                        _ if is_en_passant => Move::new_en_passant(from, to),
+                        _ if is_capture => Move::new_capture(from, to, promotion),
+                        _ => Move::new(from, to, promotion),
+                    })
+            })
+        })
+    }
+
+    pub fn knight_moves(&self, from: usize) -> Bitboard {
+        match self.get_piece_on(from) {
+            Some(Knight(color)) => KNIGHT_ATTACKS[from] & !self.occupancy(color),
+            _ => Bitboard::EMPTY,
+        }
+    }
+
+    pub fn pawn_moves(&self, from: usize) -> Bitboard {
+        let Some(Pawn(color)) = self.get_piece_on(from) else {
+            return Bitboard::EMPTY;
+        };
+        let empty = !(self.all_occupancy());
+        let bit = Bitboard::from_square(from);
+        let (single, double) = match color {
+            White => {
+                let single = bit.north() & empty;
+                (single, (single & RANKS[2]).north() & empty)
+            }
+            Black => {
+                let single = bit.south() & empty;
+                (single, (single & RANKS[5]).south() & empty)
+            }
+        };
+        let mut targets = self.occupancy(color.opposite());
+        if let Some(en_passant_target) = self.en_passant_target {
+            targets |= Bitboard::from_square(en_passant_target as usize);
+        }
+        single | double | (PAWN_ATTACKS[color.index()][from] & targets)
+    }
+
+    pub fn is_in_check(&self, color: Color) -> bool {
+        self.bitboards[King(color).index()]
+            .first()
+            .is_some_and(|king| self.is_square_attacked(king, color.opposite()))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.check_consistency().map_err(|err| err.to_string())
+    }
+
+    pub fn from_fen_strict(fen: &str) -> Result<GameState, FenError> {
+        if fen.split(' ').count() < 6 {
+            return Err(FenError::TooFewFields);
+        }
+        let game_state = fen.parse::<GameState>()?;
+        game_state.check_consistency()?;
+        Ok(game_state)
+    }
+
+    fn check_consistency(&self) -> Result<(), FenError> {
+        for color in Color::ALL {
+            match self.bitboards[King(color).index()].count() {
+                0 => return Err(FenError::MissingKing(color)),
+                1 => {}
+                _ => return Err(FenError::ExtraKing(color)),
+            }
+        }
+        let mover = self.active_color;
+        if self.is_in_check(mover.opposite()) {
+            return Err(FenError::OpponentInCheck(mover.opposite()));
+        }
+        let pawns = self.bitboards[Pawn(White).index()] | self.bitboards[Pawn(Black).index()];
+        if !(pawns & (RANKS[0] | RANKS[7])).is_empty() {
+            return Err(FenError::PawnOnBackRank);
+        }
+        for (right, &allowed) in self.castling_rights.iter().enumerate() {
+            let color = Color::ALL[right / 2];
+            let home = if color == White { 0 } else { 56 };
+            let king = self.bitboards[King(color).index()].first().unwrap_or(64);
+            let rook_file = self.castling_rook_files[right] as usize;
+            let rook_side = if right % 2 == 0 {
+                rook_file > king % 8
+            } else {
+                rook_file < king % 8
+            };
+            if allowed
+                && (king / 8 != home / 8
+                    || !rook_side
+                    || !self.bitboards[Rook(color).index()].contains(home + rook_file))
+            {
+                return Err(FenError::BadCastling(format!(
+                    "{} without a {color:?} king and rook in place",
+                    "KQkq".as_bytes()[right] as char
+                )));
+            }
+        }
+        if let Some(target) = self.en_passant_target {
+            if !self.en_passant_target_consistent(target as usize) {
+                return Err(FenError::BadEnPassant(square_name(target as usize)));
+            }
+        }
+        Ok(())
+    }
+
+    fn en_passant_target_consistent(&self, target: usize) -> bool {
+        let mover = self.active_color;
+        let (rank, pushed, origin) = match mover {
+            White => (5, target.wrapping_sub(8), target + 8),
+            Black => (2, target + 8, target.wrapping_sub(8)),
+        };
+        target / 8 == rank
+            && self.bitboards[Pawn(mover.opposite()).index()].contains(pushed)
+            && !self.all_occupancy().contains(target)
+            && !self.all_occupancy().contains(origin)
+    }
+
+    // Only a target some pawn of the side to move attacks changes the position;
+    // a dangling one left behind by a double push is ignored when hashing.
+    pub fn en_passant_capture_available(&self) -> bool {
+        self.en_passant_target.is_some_and(|target| {
+            let target = target as usize;
+            self.en_passant_target_consistent(target)
+                && !(PAWN_ATTACKS[self.active_color.opposite().index()][target]
+                    & self.bitboards[Pawn(self.active_color).index()])
+                .is_empty()
+        })
+    }
+
+    pub fn pinned_pieces(&self, color: Color) -> Bitboard {
+        let king = self.bitboards[King(color).index()];
+        let Some(king_square) = king.first() else {
+            return Bitboard::EMPTY;
+        };
+        let own = self.occupancy(color);
+        let occupancy = own | self.occupancy(color.opposite());
+        let enemy = color.opposite();
+        let queens = self.bitboards[Queen(enemy).index()];
+        let rooks = self.bitboards[Rook(enemy).index()] | queens;
+        let bishops = self.bitboards[Bishop(enemy).index()] | queens;
+        let mut pinned = Bitboard::EMPTY;
+        for (sliders, directions) in [(rooks, &ROOK_DIRECTIONS), (bishops, &BISHOP_DIRECTIONS)] {
+            let snipers = Self::sliding_attacks(king_square, Bitboard::EMPTY, directions) & sliders;
+            for sniper in squares(snipers) {
+                let between =
+                    Self::sliding_attacks(king_square, Bitboard::from_square(sniper), directions)
+                        & Self::sliding_attacks(sniper, king, directions);
+                let blockers = between & occupancy;
+                if blockers.count() == 1 && !(blockers & own).is_empty() {
+                    pinned |= blockers;
+                }
+            }
+        }
+        pinned
+    }
+
+    pub fn attackers_of(&self, square: usize, by: Color) -> Bitboard {
+        self.attackers_through(square, by, self.all_occupancy())
+    }
+
+    pub fn attackers_to(&self, square: Square, color: Color) -> Bitboard {
+        self.attackers_of(square.index(), color)
+    }
+
+    pub(crate) fn attackers_through(
+        &self,
+        square: usize,
+        by: Color,
+        occupancy: Bitboard,
+    ) -> Bitboard {
+        let queens = self.bitboards[Queen(by).index()];
+        ((PAWN_ATTACKS[by.opposite().index()][square] & self.bitboards[Pawn(by).index()])
+            | (KNIGHT_ATTACKS[square] & self.bitboards[Knight(by).index()])
+            | (KING_ATTACKS[square] & self.bitboards[King(by).index()])
+            | (self.bishop_attacks(square, occupancy)
+                & (self.bitboards[Bishop(by).index()] | queens))
+            | (self.rook_attacks(square, occupancy) & (self.bitboards[Rook(by).index()] | queens)))
+            & occupancy
+    }
+
+    pub fn is_square_attacked(&self, square: usize, by: Color) -> bool {
+        let occupancy = self.all_occupancy();
+        let queens = self.bitboards[Queen(by).index()];
+        !(PAWN_ATTACKS[by.opposite().index()][square] & self.bitboards[Pawn(by).index()]).is_empty()
+            || !(KNIGHT_ATTACKS[square] & self.bitboards[Knight(by).index()]).is_empty()
+            || !(KING_ATTACKS[square] & self.bitboards[King(by).index()]).is_empty()
+            || !(self.bishop_attacks(square, occupancy)
+                & (self.bitboards[Bishop(by).index()] | queens))
+                .is_empty()
+            || !(self.rook_attacks(square, occupancy) & (self.bitboards[Rook(by).index()] | queens))
+                .is_empty()
+    }
+
+    pub fn king_moves(&self, from: usize) -> Bitboard {
+        match self.get_piece_on(from) {
+            Some(King(color)) => {
+                (KING_ATTACKS[from] & !self.occupancy(color)) | self.castling_moves(from, color)
+            }
+            _ => Bitboard::EMPTY,
+        }
+    }
+
+    fn castling_moves(&self, from: usize, color: Color) -> Bitboard {
+        let occupancy = self.all_occupancy();
+        let rooks = self.bitboards[Rook(color).index()];
+        self.castlings(color, from)
+            .filter(|castling| {
+                rooks.contains(castling.rook_from) && (castling.between() & occupancy).is_empty()
+            })
+            .fold(Bitboard::EMPTY, |moves, castling| {
+                moves | Bitboard::from_square(castling.king_target())
+            })
+    }
+
+    #[cfg(feature = "std")]
+    pub fn rook_attacks(&self, from: usize, occupancy: Bitboard) -> Bitboard {
+        magic::rook_attacks(from, occupancy)
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn rook_attacks(&self, from: usize, occupancy: Bitboard) -> Bitboard {
+        Self::sliding_attacks(from, occupancy, &ROOK_DIRECTIONS)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn bishop_attacks(&self, from: usize, occupancy: Bitboard) -> Bitboard {
+        magic::bishop_attacks(from, occupancy)
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn bishop_attacks(&self, from: usize, occupancy: Bitboard) -> Bitboard {
+        Self::sliding_attacks(from, occupancy, &BISHOP_DIRECTIONS)
+    }
+
+    pub fn queen_attacks(&self, from: usize, occupancy: Bitboard) -> Bitboard {
+        self.rook_attacks(from, occupancy) | self.bishop_attacks(from, occupancy)
+    }
+
+    fn sliding_attacks(
+        from: usize,
+        occupancy: Bitboard,
+        directions: &[(i8, Bitboard)],
+    ) -> Bitboard {
+        let mut attacks = Bitboard::EMPTY;
+        for (shift, wrapped) in directions {
+            let mut bit = Bitboard::from_square(from);
+            loop {
+                bit = Bitboard(if *shift > 0 {
+                    bit.0 << shift
+                } else {
+                    bit.0 >> -shift
+                }) & !*wrapped;
+                if bit.is_empty() {
+                    break;
+                }
+                attacks |= bit;
+                if !(bit & occupancy).is_empty() {
+                    break;
+                }
+            }
+        }
+        attacks
+    }
+
+    pub fn white_occupancy(&self) -> Bitboard {
+        self.occupancy(White)
+    }
+
+    pub fn black_occupancy(&self) -> Bitboard {
+        self.occupancy(Black)
+    }
+
+    pub fn all_occupancy(&self) -> Bitboard {
+        self.occupancy[0] | self.occupancy[1]
+    }
+
+    fn occupancy(&self, color: Color) -> Bitboard {
+        self.occupied_by(color)
+    }
+
+    pub fn get_board_state(&self) -> [[Option<Piece>; 8]; 8] {
+        let mut board = [[None; 8]; 8];
+        for (rank, row) in board.iter_mut().enumerate() {
+            for (file, square) in row.iter_mut().enumerate() {
+                *square = self.get_piece_at(rank, file);
+            }
+        }
+        board
+    }
+
+    pub fn diff(&self, other: &GameState) -> Vec<String> {
+        let mut differences = Vec::new();
+        for rank in 0..8 {
+            for (file, file_name) in RANK_MATRIX.iter().enumerate() {
+                let ours = self.get_piece_at(rank, file);
+                let theirs = other.get_piece_at(rank, file);
+                if ours != theirs {
+                    differences.push(format!(
+                        "{}{}: {} vs {}",
+                        file_name,
+                        rank + 1,
+                        Self::describe_square(ours),
+                        Self::describe_square(theirs)
+                    ));
+                }
+            }
+        }
+        if self.active_color != other.active_color {
+            differences.push(format!(
+                "active color {:?} vs {:?}",
+                self.active_color, other.active_color
+            ));
+        }
+        if self.castling_rights != other.castling_rights
+            || self.castling_rook_files != other.castling_rook_files
+        {
+            differences.push(format!(
+                "castling {} vs {}",
+                self.castling_fen(),
+                other.castling_fen()
+            ));
+        }
+        if self.en_passant_target != other.en_passant_target {
+            differences.push(format!(
+                "en passant {:?} vs {:?}",
+                self.en_passant_target, other.en_passant_target
+            ));
+        }
+        if self.half_move_clock != other.half_move_clock {
+            differences.push(format!(
+                "half move clock {} vs {}",
+                self.half_move_clock, other.half_move_clock
+            ));
+        }
+        if self.full_move_number != other.full_move_number {
+            differences.push(format!(
+                "full move number {} vs {}",
+                self.full_move_number, other.full_move_number
+            ));
+        }
+        differences
+    }
+
+    fn describe_square(piece: Option<Piece>) -> String {
+        match piece {
+            Some(piece) => format!("{piece:?}"),
+            None => "empty".to_string(),
+        }
+    }
+
+    fn castling_fen(&self) -> String {
+        let kings =
+            Color::ALL.map(|color| self.bitboards[King(color).index()].first().unwrap_or(64));
+        let classic = (0..4).all(|right| {
+            !self.castling_rights[right]
+                || (kings[right / 2] % 8 == 4
+                    && self.castling_rook_files[right] == CLASSIC_ROOK_FILES[right])
+        });
+        self.castling_field(|_| classic)
+    }
+
+    pub fn shredder_castling(&self) -> String {
+        self.castling_field(|_| false)
+    }
+
+    pub fn xfen_castling(&self) -> String {
+        self.castling_field(|right| {
+            outermost_rook_file(&self.bitboards, right) == Some(self.castling_rook_files[right])
+        })
+    }
+
+    fn castling_field(&self, use_letter: impl Fn(usize) -> bool) -> String {
+        let mut buffer = "".to_string();
+        for (i, can_castle) in self.castling_rights.iter().enumerate() {
+            if !can_castle {
+                continue;
+            }
+            let char = if use_letter(i) {
+                "KQkq".as_bytes()[i]
+            } else {
+                b'A' + self.castling_rook_files[i]
+            } as char;
+            if i >= 2 {
+                buffer.push(char.to_ascii_lowercase());
+            } else {
+                buffer.push(char);
+            }
+        }
+        if buffer.is_empty() {
+            buffer = "-".to_string();
+        }
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::ToString, vec::Vec};
+
+    use crate::{
+        Bitboard, Color, Color::*, FenError, File, GameResult, GameState, Move, Piece::*, Rank,
+        Square,
+    };
+
+    #[test]
+    fn board_state() {
+        let game_state = GameState::default();
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            format!("{game_state}")
+        );
+    }
+
+    #[test]
+    fn board_from_fen() {
+        let game_state = GameState::default();
+        let default_fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let actual = default_fen.parse::<GameState>();
+        assert!(actual.is_ok());
+        assert_eq!(
+            game_state,
+            actual.unwrap()
+        );
+    }
+
+    #[test]
+    fn diff_reports_moved_piece() {
+        let before = GameState::default();
+        let after = "rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            vec![
+                "g1: Knight(White) vs empty".to_string(),
+                "f3: empty vs Knight(White)".to_string(),
+            ],
+            before.diff(&after)
+        );
+        assert!(before.diff(&GameState::default()).is_empty());
+    }
+
+    #[test]
+    fn knight_moves_center() {
+        let game_state = "4k3/8/8/8/3N4/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // d4: b3, b5, c2, c6, e2, e6, f3, f5
+        assert_eq!(0x0000142200221400, game_state.knight_moves(27));
+    }
+
+    #[test]
+    fn knight_moves_corner() {
+        let game_state = "4k3/8/8/8/8/8/8/N3K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // a1: b3, c2
+        assert_eq!(0x0000000000020400, game_state.knight_moves(0));
+        let game_state = "4k2n/8/8/8/8/8/8/4K3 b - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // h8: g6, f7
+        assert_eq!(0x0020400000000000, game_state.knight_moves(63));
+    }
+
+    #[test]
+    fn knight_moves_blocked_by_friendly_pieces() {
+        let game_state = "4k3/8/2P1P3/1P3P2/3N4/1P3P2/2P1P3/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(0, game_state.knight_moves(27));
+        assert_eq!(0, game_state.knight_moves(28));
+    }
+
+    #[test]
+    fn rook_attacks_stop_at_blockers() {
+        let game_state = "4k3/8/3p4/8/3R1p2/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let occupancy = game_state
+            .bitboards
+            .iter()
+            .fold(Bitboard::EMPTY, |all, bitboard| all | *bitboard);
+        let attacks = game_state.rook_attacks(27, occupancy);
+        // d5, d6, e4, f4 are attacked; d7 and g4 are behind the blockers
+        for square in [35, 43, 28, 29, 26, 24, 19, 3] {
+            assert!(attacks.contains(square), "square {square}");
+        }
+        for square in [51, 59, 30, 31] {
+            assert!(!attacks.contains(square), "square {square}");
+        }
+        assert_eq!(10, attacks.count());
+    }
+
+    #[test]
+    fn bishop_attacks_do_not_wrap() {
+        let game_state = GameState::default();
+        // h1 on an empty board: the long diagonal only
+        assert_eq!(
+            0x0102040810204000,
+            game_state.bishop_attacks(7, Bitboard::EMPTY)
+        );
+        // a4 on an empty board: b5..e8 and b3..d1
+        assert_eq!(
+            0x1008040200020408,
+            game_state.bishop_attacks(24, Bitboard::EMPTY)
+        );
+    }
+
+    #[test]
+    fn queen_attacks_combine_rook_and_bishop() {
+        let game_state = GameState::default();
+        assert_eq!(
+            game_state.rook_attacks(27, Bitboard::EMPTY)
+                | game_state.bishop_attacks(27, Bitboard::EMPTY),
+            game_state.queen_attacks(27, Bitboard::EMPTY)
+        );
+        assert_eq!(27, game_state.queen_attacks(27, Bitboard::EMPTY).count());
+    }
+
+    #[test]
+    fn pawn_moves_from_start() {
+        let game_state = GameState::default();
+        for square in 8..16 {
+            let moves = game_state.pawn_moves(square);
+            assert_eq!((1 << (square + 8)) | (1 << (square + 16)), moves);
+        }
+        for square in 48..56 {
+            let moves = game_state.pawn_moves(square);
+            assert_eq!((1 << (square - 8)) | (1 << (square - 16)), moves);
+        }
+    }
+
+    #[test]
+    fn pawn_moves_blocked() {
+        let game_state = "4k3/8/8/8/8/4n3/4P3/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(0, game_state.pawn_moves(12));
+        let game_state = "4k3/8/8/8/4n3/8/4P3/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // e3 only, the double push lands on the knight
+        assert_eq!(1 << 20, game_state.pawn_moves(12));
+    }
+
+    #[test]
+    fn pawn_moves_captures_and_promotion() {
+        let game_state = "2n3k1/3P4/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // d8 push and the c8 capture
+        assert_eq!((1 << 59) | (1 << 58), game_state.pawn_moves(51));
+    }
+
+    #[test]
+    fn pawn_moves_en_passant() {
+        let game_state = "4k3/8/8/3Pp3/8/8/8/4K3 w - - 0 2"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(1 << 43, game_state.pawn_moves(35));
+        let game_state = "4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 2"
+            .parse::<GameState>()
+            .unwrap();
+        // d6 push and the e6 en passant capture
+        assert_eq!((1 << 43) | (1 << 44), game_state.pawn_moves(35));
+    }
+
+    #[test]
+    fn king_moves_from_start() {
+        let game_state = GameState::default();
+        assert_eq!(0, game_state.king_moves(4));
+        assert_eq!(0, game_state.king_moves(60));
+    }
+
+    #[test]
+    fn king_moves_do_not_wrap() {
+        let game_state = "7k/8/8/8/8/8/8/K7 w - - 0 1".parse::<GameState>().unwrap();
+        // a1: a2, b1, b2
+        assert_eq!(0x0000000000000302, game_state.king_moves(0));
+        // h8: g8, g7, h7
+        assert_eq!(0x40C0000000000000, game_state.king_moves(63));
+    }
+
+    #[test]
+    fn king_moves_castling() {
+        let game_state = "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/RNBQK2R w Kq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // f1 and the castling target g1
+        assert_eq!((1 << 5) | (1 << 6), game_state.king_moves(4));
+        // d8, f8 and the castling target c8; g8 needs the missing k right
+        assert_eq!((1 << 59) | (1 << 61) | (1 << 58), game_state.king_moves(60));
+    }
+
+    #[test]
+    fn play_move_double_push() {
+        let game_state = GameState::default()
+            .play_move(Move::new(12, 28, None))
+            .unwrap();
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            game_state.to_string()
+        );
+
+        let game_state = game_state.play_move(Move::new(62, 45, None)).unwrap();
+        assert_eq!(
+            "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 1 2",
+            game_state.to_string()
+        );
+    }
+
+    #[test]
+    fn play_move_wrong_color() {
+        assert!(GameState::default()
+            .play_move(Move::new(52, 36, None))
+            .is_err());
+        assert!(GameState::default()
+            .play_move(Move::new(28, 36, None))
+            .is_err());
+    }
+
+    #[test]
+    fn play_move_captures() {
+        let game_state = "4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 2"
+            .parse::<GameState>()
+            .unwrap();
+        let game_state = game_state.play_move(Move::new(35, 44, None)).unwrap();
+        assert_eq!("4k3/8/4P3/8/8/8/8/4K3 b - - 0 2", game_state.to_string());
+
+        let game_state = "4k3/8/8/3p4/8/8/8/3RK3 w - - 7 2"
+            .parse::<GameState>()
+            .unwrap()
+            .play_move(Move::new(3, 35, None))
+            .unwrap();
+        assert_eq!("4k3/8/8/3R4/8/8/8/4K3 b - - 0 2", game_state.to_string());
+    }
+
+    #[test]
+    fn play_move_castles() {
+        let game_state = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap()
+            .play_move(Move::new(4, 6, None))
+            .unwrap()
+            .play_move(Move::new(60, 58, None))
+            .unwrap();
+        assert!(game_state
+            .to_string()
+            .starts_with("2kr3r/8/8/8/8/8/8/R4RK1 w "));
+    }
+
+    #[test]
+    fn unmake_move_round_trip() {
+        let mut game_state = "r3k2r/pPpp1ppp/8/3Pp3/8/8/PPP2PPP/R3K2R w KQkq e6 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let mut seed: u64 = 0x2545F4914F6CDD1D;
+        for _ in 0..20 {
+            let original = game_state.clone();
+            let moves = game_state.pseudo_legal_moves();
+            for mv in moves.iter().copied() {
+                let undo = game_state.make_move(mv).unwrap();
+                game_state.unmake_move(mv, undo);
+                assert_eq!(original, game_state, "{mv}");
+            }
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            game_state
+                .make_move(moves[seed as usize % moves.len()])
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn unmake_move_restores_special_moves() {
+        let mut game_state = "r3k2r/1P6/8/3Pp3/8/8/8/R3K2R w KQkq e6 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let original = game_state.clone();
+        for (from, to, promotion) in [
+            (35, 44, None),
+            (4, 6, None),
+            (4, 2, None),
+            (49, 56, Some(Queen(White))),
+        ] {
+            let mv = Move::new(from, to, promotion);
+            let undo = game_state.make_move(mv).unwrap();
+            assert_ne!(original, game_state);
+            game_state.unmake_move(mv, undo);
+            assert_eq!(original, game_state, "{from} -> {to}");
+        }
+    }
+
+    #[test]
+    fn is_in_check_by_rook() {
+        let game_state = "4k3/8/8/8/8/8/8/4RK2 b - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(game_state.is_in_check(Black));
+        assert!(!game_state.is_in_check(White));
+    }
+
+    #[test]
+    fn is_in_check_by_knight() {
+        let game_state = "4k3/8/3N4/8/8/8/8/4K3 b - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(game_state.is_in_check(Black));
+    }
+
+    #[test]
+    fn is_in_check_blocked_bishop() {
+        let game_state = "4k3/8/8/8/1b6/8/3P4/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(!game_state.is_in_check(White));
+        let game_state = "4k3/8/8/8/1b6/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(game_state.is_in_check(White));
+    }
+
+    #[test]
+    fn is_in_check_by_pawn() {
+        let game_state = "4k3/8/8/8/8/8/5p2/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(game_state.is_in_check(White));
+        let game_state = "4k3/8/8/8/8/8/8/4Kp2 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(!game_state.is_in_check(White));
+    }
+
+    #[test]
+    fn legal_moves_from_start() {
+        assert_eq!(20, GameState::default().legal_moves().len());
+    }
+
+    #[test]
+    fn legal_moves_pinned_bishop() {
+        let game_state = "4k3/8/8/8/4r3/8/4B3/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let moves = game_state.legal_moves();
+        assert!(moves.iter().all(|mv| mv.from() != 12));
+        // d1, f1, d2, f2
+        assert_eq!(4, moves.len());
+    }
+
+    #[test]
+    fn legal_moves_castling_through_check() {
+        let game_state = "4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(game_state.legal_moves().contains(&Move::new(4, 6, None)));
+        assert!(game_state.legal_moves().contains(&Move::new(4, 2, None)));
+        let game_state = "4kr2/8/8/8/8/8/8/R3K2R w KQ - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(!game_state.legal_moves().contains(&Move::new(4, 6, None)));
+        assert!(game_state.legal_moves().contains(&Move::new(4, 2, None)));
+        let game_state = "4r1k1/8/8/8/8/8/8/R3K2R w KQ - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(!game_state.legal_moves().contains(&Move::new(4, 6, None)));
+        assert!(!game_state.legal_moves().contains(&Move::new(4, 2, None)));
+    }
+
+    #[test]
+    fn shredder_castling_fields() {
+        let shredder = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w AHah - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(GameState::default(), shredder);
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            shredder.to_string()
+        );
+        let fen = "bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w GEge - 0 1";
+        let chess960 = fen.parse::<GameState>().unwrap();
+        assert_eq!(fen, chess960.to_string());
+        assert_eq!(
+            Ok(chess960),
+            "bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w EGeg - 0 1".parse()
+        );
+        assert_eq!(
+            Err(FenError::BadCastling("F".to_string())),
+            "bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w F - 0 1".parse::<GameState>()
+        );
+    }
+
+    #[test]
+    fn chess960_castling_toward_rook() {
+        let game_state = "4k3/8/8/8/8/8/8/4RKR1 w GE - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let legal_moves = game_state.legal_moves();
+        assert!(legal_moves.contains(&Move::new(5, 6, None)));
+        assert!(legal_moves.contains(&Move::new(5, 4, None)));
+        assert!(game_state
+            .play_move(Move::new(5, 6, None))
+            .unwrap()
+            .to_string()
+            .starts_with("4k3/8/8/8/8/8/8/4RRK1 b"));
+        assert!(game_state
+            .play_move(Move::new(5, 4, None))
+            .unwrap()
+            .to_string()
+            .starts_with("4k3/8/8/8/8/8/8/2KR2R1 b"));
+        let mut scratch = game_state.clone();
+        let undo = scratch.make_move(Move::new(5, 4, None)).unwrap();
+        scratch.unmake_move(Move::new(5, 4, None), undo);
+        assert_eq!(game_state, scratch);
+    }
+
+    #[test]
+    fn perft_chess960() {
+        let game_state = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(21, game_state.perft(1));
+        assert_eq!(528, game_state.perft(2));
+        assert_eq!(12189, game_state.perft(3));
+    }
+
+    #[test]
+    fn legal_moves_expand_promotions() {
+        let game_state = "7k/4P3/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let promotions: Vec<_> = game_state
+            .legal_moves()
+            .into_iter()
+            .filter(|mv| mv.from() == 52)
+            .collect();
+        assert_eq!(
+            vec![
+                Move::new(52, 60, Some(Queen(White))),
+                Move::new(52, 60, Some(Rook(White))),
+                Move::new(52, 60, Some(Bishop(White))),
+                Move::new(52, 60, Some(Knight(White))),
+            ],
+            promotions
+        );
+    }
+
+    #[test]
+    fn perft_start_position() {
+        let game_state = GameState::default();
+        assert_eq!(20, game_state.perft(1));
+        assert_eq!(400, game_state.perft(2));
+        assert_eq!(8902, game_state.perft(3));
+    }
+
+    #[test]
+    fn perft_kiwipete() {
+        let game_state = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(48, game_state.perft(1));
+        assert_eq!(2039, game_state.perft(2));
+        assert_eq!(97862, game_state.perft(3));
+    }
+
+    #[test]
+    fn perft_divide_sums_to_perft() {
+        let game_state = GameState::default();
+        let divide = game_state.perft_divide(3);
+        assert_eq!(20, divide.len());
+        assert!(divide.contains(&(Move::new(12, 28, None), 600)));
+        assert_eq!(8902, divide.iter().map(|(_, nodes)| nodes).sum::<u64>());
+    }
+
+    #[test]
+    fn fifty_move_draw() {
+        let game_state = "4k3/8/8/8/8/8/8/R3K3 w - - 100 80"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(game_state.is_fifty_move_draw());
+        assert!(game_state.is_draw_by_rule());
+        let game_state = "4k3/8/8/8/8/8/8/R3K3 w - - 99 80"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(!game_state.is_fifty_move_draw());
+        assert!(!game_state.is_draw_by_rule());
+    }
+
+    #[test]
+    fn stalemate_is_draw() {
+        let game_state = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(game_state.is_draw_by_rule());
+        let game_state = "6Qk/8/6K1/8/8/8/8/8 b - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(!game_state.is_draw_by_rule());
+    }
+
+    #[test]
+    fn insufficient_material() {
+        for fen in [
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/1N2K3 w - - 0 1",
+            "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        ] {
+            let game_state = fen.parse::<GameState>().unwrap();
+            assert!(game_state.is_insufficient_material(), "{fen}");
+            assert!(game_state.is_draw_by_rule(), "{fen}");
+        }
+    }
+
+    #[test]
+    fn sufficient_material() {
+        for fen in [
+            "4k3/8/8/8/8/8/8/1N2K1N1 w - - 0 1",
+            "2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1",
+            "4kn2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+        ] {
+            let game_state = fen.parse::<GameState>().unwrap();
+            assert!(!game_state.is_insufficient_material(), "{fen}");
+        }
+    }
+
+    #[test]
+    fn result_checkmate() {
+        let game_state = "R5k1/5ppp/8/8/8/8/8/6K1 b - - 1 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(GameResult::Checkmate(White), game_state.result());
+        assert!(game_state.is_checkmate());
+        assert!(!game_state.is_stalemate());
+    }
+
+    #[test]
+    fn result_stalemate() {
+        let game_state = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(GameResult::Stalemate, game_state.result());
+        assert!(game_state.is_stalemate());
+        assert!(!game_state.is_checkmate());
+        assert!(!GameState::default().is_checkmate());
+        assert!(!GameState::default().is_stalemate());
+    }
+
+    #[test]
+    fn result_draw_and_ongoing() {
+        let game_state = "4k3/8/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(GameResult::Draw, game_state.result());
+        assert_eq!(GameResult::Ongoing, GameState::default().result());
+    }
+
+    #[test]
+    fn en_passant_target_from_fen() {
+        let game_state = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(Some(20), game_state.en_passant_target);
+        let game_state = "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(Some(43), game_state.en_passant_target);
+        for bad in ["e9", "i3", "e", "e33", "3e"] {
+            let fen = format!("4k3/8/8/8/8/8/8/4K3 w - {bad} 0 1");
+            assert!(fen.parse::<GameState>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn en_passant_capture_available() {
+        let capturable = "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(capturable.en_passant_capture_available());
+        let dangling = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(!dangling.en_passant_capture_available());
+        assert!(!GameState::default().en_passant_capture_available());
+        let stray = "4k3/8/8/8/8/8/3p4/4K3 b - e3 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(!stray.en_passant_capture_available());
+    }
+
+    #[test]
+    fn en_passant_target_display() {
+        let fen = "rnbqkbnr/pp1ppppp/8/1Pp5/8/8/P1PPPPPP/RNBQKBNR w KQkq c6 0 3";
+        let game_state = fen.parse::<GameState>().unwrap();
+        assert_eq!(fen, game_state.to_string());
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+        assert_eq!(fen, fen.parse::<GameState>().unwrap().to_string());
+    }
+
+    #[test]
+    fn long_game_counters() {
+        let fen = "4k3/8/8/8/8/8/8/R3K3 w - - 300 500";
+        let game_state = fen.parse::<GameState>().unwrap();
+        assert_eq!(500, game_state.full_move_number);
+        assert_eq!(300, game_state.half_move_clock);
+        assert_eq!(fen, game_state.to_string());
+    }
+
+    #[test]
+    fn board_validation() {
+        assert_eq!(
+            Err(FenError::BadRankLength {
+                rank: 3,
+                squares: 9
+            }),
+            "4k3/8/8/8/8/3P5/8/4K3 w - - 0 1".parse::<GameState>()
+        );
+        assert_eq!(
+            Err(FenError::BadRankLength {
+                rank: 8,
+                squares: 7
+            }),
+            "4k2/8/8/8/8/8/8/4K3 w - - 0 1".parse::<GameState>()
+        );
+        assert_eq!(
+            Err(FenError::BadRankCount(7)),
+            "4k3/8/8/8/8/8/4K3 w - - 0 1".parse::<GameState>()
+        );
+        assert_eq!(
+            Err(FenError::MissingKing(Black)),
+            "8/8/8/8/8/8/8/4K3 w - - 0 1".parse::<GameState>()
+        );
+        assert_eq!(
+            Err(FenError::ExtraKing(White)),
+            "4k3/8/8/8/8/8/8/3KK3 w - - 0 1".parse::<GameState>()
+        );
+        assert_eq!(
+            Err(FenError::BadPiece('x')),
+            "4k2x/8/8/8/8/8/8/4K3 w - - 0 1".parse::<GameState>()
+        );
+        assert_eq!(
+            "rank 3 describes 9 squares",
+            "4k3/8/8/8/8/3P5/8/4K3 w - - 0 1"
+                .parse::<GameState>()
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "no black king",
+            "8/8/8/8/8/8/8/4K3 w - - 0 1"
+                .parse::<GameState>()
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn fen_errors() {
+        assert_eq!(
+            Err(FenError::TooFewFields),
+            "4k3/8/8/8/8/8/8/4K3 w -".parse::<GameState>()
+        );
+        assert_eq!(
+            Err(FenError::MissingField(2)),
+            "4k3/8/8/8/8/8/8/4K3 w  - 0 1".parse::<GameState>()
+        );
+        assert_eq!(
+            Err(FenError::BadCastling("KX".to_string())),
+            "4k3/8/8/8/8/8/8/4K3 w KX - 0 1".parse::<GameState>()
+        );
+        assert_eq!(
+            Err(FenError::BadEnPassant("e9".to_string())),
+            "4k3/8/8/8/8/8/8/4K3 w - e9 0 1".parse::<GameState>()
+        );
+        assert!(matches!(
+            "4k3/8/8/8/8/8/8/4K3 w - - x 1".parse::<GameState>(),
+            Err(FenError::BadNumber(_))
+        ));
+    }
+
+    #[test]
+    fn square_accessors() {
+        let game_state = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let e4 = Square::from_algebraic("e4").unwrap();
+        assert_eq!(Some(Pawn(White)), game_state.get_piece_on_square(e4));
+        assert_eq!(
+            None,
+            game_state.get_piece_on_square(Square::new(File::E, Rank::Second))
+        );
+        assert_eq!(
+            Some("e3".to_string()),
+            game_state
+                .get_en_passant_square()
+                .map(|square| square.to_string())
+        );
+    }
+
+    #[test]
+    fn attackers_of_square() {
+        let game_state = "4k3/8/8/3r4/8/4p3/8/3QK3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // d2 is attacked by the e3 pawn and the d5 rook
+        assert_eq!((1 << 20) | (1 << 35), game_state.attackers_of(11, Black));
+        // ...and defended by the queen and king
+        assert_eq!((1 << 3) | (1 << 4), game_state.attackers_of(11, White));
+        assert_eq!(0, game_state.attackers_of(63, White));
+    }
+
+    #[test]
+    fn attackers_of_blocked_sliders() {
+        let game_state = "4k3/8/8/3r4/3n4/8/8/3QK3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // the knight on d4 shields d2 from the rook
+        assert!(!game_state.attackers_of(11, Black).contains(35));
+    }
+
+    #[test]
+    fn attackers_to_square() {
+        let game_state = "4k3/8/8/3r4/8/4p3/8/3QK3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let d2 = Square::from_algebraic("d2").unwrap();
+        assert_eq!(
+            game_state.attackers_of(11, Black),
+            game_state.attackers_to(d2, Black)
+        );
+        assert_eq!((1 << 3) | (1 << 4), game_state.attackers_to(d2, White));
+    }
+
+    #[test]
+    fn pinned_pieces() {
+        let game_state = "4r1k1/8/8/8/1b6/8/4N3/4K1B1 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // the e2 knight is pinned by the e8 rook; the g1 bishop is not on a king ray
+        assert_eq!(1 << 12, game_state.pinned_pieces(White));
+        assert_eq!(0, game_state.pinned_pieces(Black));
+    }
+
+    #[test]
+    fn pinned_pieces_diagonal_and_double_blockers() {
+        let game_state = "4k3/8/8/q7/8/2P5/3P4/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // two pieces between the king and the queen: neither is pinned
+        assert_eq!(0, game_state.pinned_pieces(White));
+        let game_state = "4k3/8/8/q7/8/8/3P4/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(1 << 11, game_state.pinned_pieces(White));
+        let game_state = "4k3/8/8/b7/8/8/3p4/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        // an enemy blocker is not a pin
+        assert_eq!(0, game_state.pinned_pieces(White));
+    }
+
+    #[test]
+    fn fen_without_move_counters() {
+        let game_state = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(GameState::default(), game_state);
+        assert_eq!(0, game_state.half_move_clock);
+        assert_eq!(1, game_state.full_move_number);
+        let game_state = "4k3/8/8/8/8/8/8/4K3 b - - 7".parse::<GameState>().unwrap();
+        assert_eq!(7, game_state.half_move_clock);
+        assert_eq!(1, game_state.full_move_number);
+        assert_eq!(
+            Err(FenError::TooFewFields),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq".parse::<GameState>()
+        );
+    }
+
+    #[test]
+    fn all_occupancy_is_union_of_piece_boards() {
+        for game_state in [
+            GameState::default(),
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+                .parse::<GameState>()
+                .unwrap(),
+        ] {
+            let union = game_state
+                .bitboards
+                .iter()
+                .fold(Bitboard::EMPTY, |all, board| all | *board);
+            assert_eq!(union, game_state.all_occupancy());
+            assert_eq!(
+                union,
+                game_state.white_occupancy() | game_state.black_occupancy()
+            );
+            assert_eq!(
+                0,
+                game_state.white_occupancy() & game_state.black_occupancy()
+            );
+        }
+        assert_eq!(0x000000000000FFFF, GameState::default().white_occupancy());
+        assert_eq!(None, GameState::default().get_piece_on(28));
+    }
+
+    #[test]
+    fn color_helpers() {
+        assert_eq!(Black, White.opposite());
+        assert_eq!(White, Black.opposite());
+        assert_eq!(vec![White, Black], Color::ALL.to_vec());
+        for color in Color::ALL {
+            assert_eq!(color, Color::ALL[color.index()]);
+        }
+    }
+
+    #[test]
+    fn castling_rights_revoked() {
+        let game_state = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let after_king = game_state.play_move(Move::new(4, 12, None)).unwrap();
+        assert_eq!("r3k2r/8/8/8/8/8/4K3/R6R b kq - 1 1", after_king.to_string());
+        let after_castling = game_state.play_move(Move::new(4, 6, None)).unwrap();
+        assert_eq!(
+            "r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1",
+            after_castling.to_string()
+        );
+        let after_rook = game_state.play_move(Move::new(7, 15, None)).unwrap();
+        assert_eq!(
+            "r3k2r/8/8/8/8/8/7R/R3K3 b Qkq - 1 1",
+            after_rook.to_string()
+        );
+        let after_capture = game_state.play_move(Move::new(0, 56, None)).unwrap();
+        assert_eq!(
+            "R3k2r/8/8/8/8/8/8/4K2R b Kk - 0 1",
+            after_capture.to_string()
+        );
+
+        let mut scratch = game_state.clone();
+        let undo = scratch.make_move(Move::new(0, 56, None)).unwrap();
+        scratch.unmake_move(Move::new(0, 56, None), undo);
+        assert_eq!(game_state, scratch);
+    }
+
+    #[test]
+    fn promotion_moves() {
+        let square = |name| Square::from_algebraic(name).unwrap();
+        let game_state = "8/4P3/8/8/8/8/6k1/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let promotions = game_state.promotion_moves(square("e7"), square("e8"));
+        assert_eq!(
+            vec![
+                Move::new(52, 60, Some(Queen(White))),
+                Move::new(52, 60, Some(Rook(White))),
+                Move::new(52, 60, Some(Bishop(White))),
+                Move::new(52, 60, Some(Knight(White))),
+            ],
+            promotions
+        );
+        let legal = game_state.legal_moves();
+        assert!(promotions.iter().all(|mv| legal.contains(mv)));
+        assert!(game_state
+            .promotion_moves(square("e7"), square("d8"))
+            .is_empty());
+        let pinned = "3b3k/4P3/8/8/7K/8/8/8 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(pinned
+            .promotion_moves(square("e7"), square("e8"))
+            .is_empty());
+        assert_eq!(4, pinned.promotion_moves(square("e7"), square("d8")).len());
+        assert!(GameState::default()
+            .promotion_moves(square("e2"), square("e4"))
+            .is_empty());
+    }
+
+    #[test]
+    fn reachable_squares() {
+        let square = |name| Square::from_algebraic(name).unwrap();
+        let game_state = "4k3/8/8/b7/8/8/3B4/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            Bitboard::from_square(18) | Bitboard::from_square(25) | Bitboard::from_square(32),
+            game_state.reachable_squares(square("d2"))
+        );
+        let game_state = "4k3/8/8/8/8/8/3B4/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(8, game_state.reachable_squares(square("d2")).count());
+        assert_eq!(
+            Bitboard::from_square(16) | Bitboard::from_square(18),
+            GameState::default().reachable_squares(square("b1"))
+        );
+        assert!(GameState::default()
+            .reachable_squares(square("e4"))
+            .is_empty());
+        assert!(GameState::default()
+            .reachable_squares(square("e7"))
+            .is_empty());
+    }
+
+    #[test]
+    fn pawn_moves_reset_half_move_clock() {
+        for (fen, from, to, promotion) in [
+            ("4k3/8/8/8/8/8/4P3/4K3 w - - 12 30", 12, 20, None),
+            ("4k3/8/8/8/8/8/4P3/4K3 w - - 12 30", 12, 28, None),
+            ("4k3/8/8/3p4/4P3/8/8/4K3 w - - 12 30", 28, 35, None),
+            ("4k3/8/8/3pP3/8/8/8/4K3 w - d6 12 30", 36, 43, None),
+            (
+                "8/4P1k1/8/8/8/8/8/4K3 w - - 12 30",
+                52,
+                60,
+                Some(Queen(White)),
+            ),
+            (
+                "3r2k1/4P3/8/8/8/8/8/4K3 w - - 12 30",
+                52,
+                59,
+                Some(Knight(White)),
+            ),
+            ("4k3/8/8/8/8/8/4p3/K7 b - - 12 30", 12, 4, Some(Rook(Black))),
+        ] {
+            let game_state = fen.parse::<GameState>().unwrap();
+            let next = game_state
+                .play_move(Move::new(from, to, promotion))
+                .unwrap();
+            assert_eq!(0, next.half_move_clock, "{fen} {from}-{to}");
+        }
+        let game_state = "4k3/8/8/8/8/8/4P3/1N2K3 w - - 12 30"
+            .parse::<GameState>()
+            .unwrap();
+        let next = game_state.play_move(Move::new(1, 18, None)).unwrap();
+        assert_eq!(13, next.half_move_clock);
+        let game_state = "4k3/8/8/8/8/8/8/4K2R w K - 12 30"
+            .parse::<GameState>()
+            .unwrap();
+        let next = game_state.play_move(Move::new(4, 6, None)).unwrap();
+        assert_eq!(13, next.half_move_clock);
+    }
+
+    #[test]
+    fn double_push_sets_en_passant_target() {
+        let after_e4 = GameState::default()
+            .play_move(Move::new(12, 28, None))
+            .unwrap();
+        assert_eq!(
+            Some(Square::new(File::E, Rank::Third)),
+            after_e4.get_en_passant_square()
+        );
+        let after_nf6 = after_e4.play_move(Move::new(62, 45, None)).unwrap();
+        assert_eq!(None, after_nf6.get_en_passant_square());
+        let after_e3 = GameState::default()
+            .play_move(Move::new(12, 20, None))
+            .unwrap();
+        assert_eq!(None, after_e3.get_en_passant_square());
+    }
+
+    #[test]
+    fn play_move_promotions() {
+        let game_state = "8/4P3/8/8/8/8/8/k6K w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let queened = game_state
+            .play_move(Move::new(52, 60, Some(Queen(White))))
+            .unwrap();
+        assert_eq!(1 << 60, *queened.get_bitboard(Queen(White)));
+        assert_eq!(0, *queened.get_bitboard(Pawn(White)));
+        let knighted = game_state
+            .play_move(Move::new(52, 60, Some(Knight(White))))
+            .unwrap();
+        assert_eq!(1 << 60, *knighted.get_bitboard(Knight(White)));
+        assert_eq!(0, *knighted.get_bitboard(Knight(Black)));
+        assert_eq!(
+            queened,
+            game_state.play_move(Move::new(52, 60, None)).unwrap()
+        );
+        assert_eq!(
+            knighted,
+            game_state
+                .play_move(Move::new(52, 60, Some(Knight(Black))))
+                .unwrap()
+        );
+        assert!(game_state
+            .play_move(Move::new(7, 15, Some(Queen(White))))
+            .is_err());
+        let backwards = "4k3/8/8/8/8/8/P7/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            Err("White cannot promote to Queen(Black)".to_string()),
+            backwards.play_move(Move::new(8, 0, Some(Queen(White))))
+        );
+    }
+
+    #[test]
+    fn is_square_attacked_by_bishop() {
+        let game_state = "4k3/8/b7/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(game_state.is_square_attacked(5, Black));
+        assert!(!game_state.is_square_attacked(7, Black));
+        let game_state = "4k3/8/b7/8/8/8/4P3/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(!game_state.is_square_attacked(5, Black));
+        assert!(game_state.is_square_attacked(12, Black));
+    }
+
+    #[test]
+    fn validate_positions() {
+        assert_eq!(Ok(()), GameState::default().validate());
+        let after_e4 = GameState::default()
+            .play_move(Move::new(12, 28, None))
+            .unwrap();
+        assert_eq!(Ok(()), after_e4.validate());
+        let rook_endgame = "4k3/8/8/8/8/8/8/4KR2 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(Ok(()), rook_endgame.validate());
+        let opponent_in_check = "4k3/8/8/8/8/8/8/4R1K1 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(opponent_in_check.validate().is_err());
+        let pawn_on_first_rank = "4k3/8/8/8/8/8/8/P3K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(pawn_on_first_rank.validate().is_err());
+        let stray_en_passant = "4k3/8/8/8/8/8/8/4K3 b - e3 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(stray_en_passant.validate().is_err());
+    }
+
+    #[test]
+    fn active_color_field() {
+        for (field, color) in [("w", White), ("b", Black)] {
+            let fen = format!("4k3/8/8/8/8/8/8/4K3 {field} - - 0 1");
+            assert_eq!(color, fen.parse::<GameState>().unwrap().active_color);
+        }
+        for field in ["W", "x"] {
+            let fen = format!("4k3/8/8/8/8/8/8/4K3 {field} - - 0 1");
+            assert_eq!(
+                Err(FenError::BadActiveColor(field.to_string())),
+                fen.parse::<GameState>()
+            );
+        }
+    }
+
+    #[test]
+    fn null_move_round_trip() {
+        let game_state = "4k3/8/8/3Pp3/8/8/8/4K3 w - e6 7 20"
+            .parse::<GameState>()
+            .unwrap();
+        let passed = game_state.make_null_move();
+        assert_eq!("4k3/8/8/3Pp3/8/8/8/4K3 b - - 7 20", passed.to_string());
+        let mut scratch = game_state.clone();
+        let undo = scratch.apply_null_move();
+        assert_eq!(passed, scratch);
+        scratch.unmake_null_move(undo);
+        assert_eq!(game_state, scratch);
+        assert_eq!(game_state.to_string(), scratch.to_string());
+    }
+
+    #[test]
+    fn generated_moves_carry_special_flags() {
+        let game_state = "r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let legal = game_state.legal_moves();
+        let find = |from, to| {
+            *legal
+                .iter()
+                .find(|mv| **mv == Move::new(from, to, None))
+                .unwrap()
+        };
+        assert!(find(36, 43).is_en_passant());
+        assert!(find(4, 6).is_castle());
+        assert!(find(4, 2).is_castle());
+        assert!(find(0, 56).is_capture());
+        assert!(!find(0, 8).is_capture());
+        assert!(!find(36, 44).is_capture());
+        assert_eq!(
+            legal.iter().filter(|mv| mv.is_capture()).count(),
+            legal
+                .iter()
+                .filter(|mv| game_state.move_flags(mv.from(), mv.to()).is_capture)
+                .count()
+        );
+        let chess960 = "4k3/8/8/8/8/8/8/1R2K1R1 w GB - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(chess960
+            .legal_moves()
+            .iter()
+            .filter(|mv| mv.is_castle())
+            .all(|mv| !mv.is_capture()));
+        assert!(chess960.parse_uci("e1g1").unwrap().is_castle());
+        assert!(chess960.parse_uci("e1b1").unwrap().is_castle());
+    }
+
+    #[test]
+    fn legal_moves_iter_is_lazy() {
+        // A list generated up front would know an upper bound on its length.
+        assert_eq!(
+            (0, None),
+            GameState::default().legal_moves_iter().size_hint()
+        );
+        assert_eq!(20, GameState::default().legal_moves_iter().count());
+        let stalemate = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(stalemate.legal_moves_iter().next().is_none());
+        assert!(!stalemate.is_in_check(Black));
+        assert_eq!(GameResult::Stalemate, stalemate.result());
+    }
+
+    #[test]
+    fn legal_move_count() {
+        assert_eq!(20, GameState::default().legal_move_count());
+        let checkmate = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(0, checkmate.legal_move_count());
+        assert!(checkmate.is_in_check(White));
+        let stalemate = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(0, stalemate.legal_move_count());
+        assert!(!stalemate.is_in_check(Black));
+    }
+
+    #[test]
+    fn play_moves_scholars_mate() {
+        let game_state = GameState::default()
+            .play_moves(&["e2e4", "e5", "Qh5", "Nc6", "f1c4", "Nf6", "Qxf7#"])
+            .unwrap();
+        assert_eq!(
+            "r1bqkb1r/pppp1Qpp/2n2n2/4p3/2B1P3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 4",
+            game_state.to_string()
+        );
+        assert_eq!(GameResult::Checkmate(White), game_state.result());
+    }
+
+    #[test]
+    fn play_moves_reports_first_bad_move() {
+        let err = GameState::default()
+            .play_moves(&["e4", "e5", "Ke3", "Nf6"])
+            .unwrap_err();
+        assert!(err.starts_with("Move 2 (Ke3)"), "{err}");
+        let err = GameState::default().play_moves(&["e2e5"]).unwrap_err();
+        assert!(err.starts_with("Move 0 (e2e5)"), "{err}");
+    }
+
+    #[test]
+    fn legal_moves_en_passant_edge_cases() {
+        let rank_pinned = "8/8/8/8/k2Pp2Q/8/8/3K4 b - d3 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(!rank_pinned.legal_moves().contains(&Move::new(28, 19, None)));
+        let evades_check = "8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert!(evades_check.is_in_check(Black));
+        assert!(evades_check
+            .legal_moves()
+            .contains(&Move::new(28, 19, None)));
+    }
+
+    #[test]
+    fn perft_reference_positions() {
+        let position_3 = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(14, position_3.perft(1));
+        assert_eq!(191, position_3.perft(2));
+        assert_eq!(2812, position_3.perft(3));
+        assert_eq!(43238, position_3.perft(4));
+        let position_5 = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(44, position_5.perft(1));
+        assert_eq!(1486, position_5.perft(2));
+        assert_eq!(62379, position_5.perft(3));
+        let divide = position_5.perft_divide(2);
+        assert_eq!(44, divide.len());
+        assert_eq!(1486, divide.iter().map(|(_, nodes)| nodes).sum::<u64>());
+    }
+
+    #[test]
+    fn from_fen_strict() {
+        assert_eq!(
+            Ok(GameState::default()),
+            GameState::from_fen_strict("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
+        );
+        assert_eq!(
+            Err(FenError::TooFewFields),
+            GameState::from_fen_strict("4k3/8/8/8/8/8/8/4K3 w - -")
+        );
+        assert_eq!(
+            Err(FenError::BadRankLength {
+                rank: 8,
+                squares: 9
+            }),
+            GameState::from_fen_strict("4k4/8/8/8/8/8/8/4K3 w - - 0 1")
+        );
+        assert_eq!(
+            Err(FenError::OpponentInCheck(Black)),
+            GameState::from_fen_strict("4k2R/8/8/8/8/8/8/4K3 w - - 0 1")
+        );
+        assert_eq!(
+            Err(FenError::PawnOnBackRank),
+            GameState::from_fen_strict("4k3/8/8/8/8/8/8/P3K3 w - - 0 1")
+        );
+        assert!(matches!(
+            GameState::from_fen_strict("4k3/8/8/8/8/8/8/4K3 w K - 0 1"),
+            Err(FenError::BadCastling(_))
+        ));
+        assert!(matches!(
+            GameState::from_fen_strict("4k3/8/8/8/8/8/8/4K2R w Q - 0 1"),
+            Err(FenError::BadCastling(_))
+        ));
+        assert!(GameState::from_fen_strict("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").is_ok());
+        assert_eq!(
+            Err(FenError::BadEnPassant("e6".to_string())),
+            GameState::from_fen_strict("4k3/8/8/8/8/8/8/4K3 w - e6 0 1")
+        );
+    }
+
+    #[test]
+    fn xfen_and_shredder_castling() {
+        // X-FEN letters name the outermost rook, so KQkq works for Chess960 starts.
+        let game_state = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9"
+                .parse::<GameState>()
+                .unwrap(),
+            game_state
+        );
+        assert_eq!("HFhf", game_state.shredder_castling());
+        assert_eq!("KQkq", game_state.xfen_castling());
+
+        // An inner rook needs its file letter in X-FEN.
+        let game_state = "4k3/8/8/8/8/8/8/R1R1K3 w C - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!("C", game_state.xfen_castling());
+        let game_state = "4k3/8/8/8/8/8/8/R1R1K3 w Q - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!("A", game_state.shredder_castling());
+        assert_eq!("Q", game_state.xfen_castling());
+
+        let game_state = GameState::default();
+        assert_eq!("HAha", game_state.shredder_castling());
+        assert_eq!("KQkq", game_state.xfen_castling());
     }
 }
@@ -0,0 +1,259 @@
+use alloc::{vec, vec::Vec};
+use std::sync::OnceLock;
+
+use crate::{Bitboard, GameState, BISHOP_DIRECTIONS, ROOK_DIRECTIONS};
+
+const ROOK_MAGICS: [u64; 64] = [
+    0x0080001020884001,
+    0x0840001000200044,
+    0x0200120040220880,
+    0x0200060010200840,
+    0x0280040080080002,
+    0x0200018802000410,
+    0x21004C0892000100,
+    0x0200004021008402,
+    0x0300800040008030,
+    0x0861808020004000,
+    0x0C01002001004014,
+    0x0011803000080180,
+    0x4802000422001008,
+    0x8800800200800400,
+    0x50040001080402F0,
+    0x103A800041001880,
+    0x0880828000C00028,
+    0x0240002008003000,
+    0x0400450015002002,
+    0x1100210008100100,
+    0x4086020010040820,
+    0x0040080110400420,
+    0x012044002801A210,
+    0x088002000444A114,
+    0x8400802180004004,
+    0x0040008080200042,
+    0x0000100080802000,
+    0x0200100100200D00,
+    0x0003008500080210,
+    0x810A040080800200,
+    0x00000104002802B0,
+    0x0000048200044124,
+    0x8000400020801080,
+    0x8C80400080802000,
+    0x8241002001004010,
+    0x8000080080801000,
+    0x22C8000901001004,
+    0x1630800401801200,
+    0xA808020001010004,
+    0x4206208402000061,
+    0x4000804000208001,
+    0x0050820100520020,
+    0x8020010010210040,
+    0xC111028810030020,
+    0x0009020488010010,
+    0x82120038100A0005,
+    0x000008011034000A,
+    0x08240400804E0015,
+    0x4081020080204200,
+    0x0800401000200140,
+    0x805004802001B080,
+    0x00212041100A0200,
+    0x3041004408013100,
+    0x0417000208040100,
+    0x10005A0810090400,
+    0x1010024104068A00,
+    0x00C9014051208202,
+    0x0046110020860042,
+    0x0200142000084101,
+    0x100500A044100109,
+    0x8282001008042002,
+    0x4A02002108C41002,
+    0x000B0000C2000401,
+    0x1080040302205082,
+];
+const BISHOP_MAGICS: [u64; 64] = [
+    0x0004080088121040,
+    0x00718A0801002050,
+    0x0508081048800012,
+    0x4084240680202010,
+    0x0024242024000200,
+    0x8005100805004092,
+    0x0081040202404230,
+    0x0802202402201104,
+    0x0080044802480200,
+    0x80802002208A0490,
+    0x0B00100482014C41,
+    0x4010110410845000,
+    0x0000084840088680,
+    0x0000A20250060180,
+    0x0410408A080B4000,
+    0x4002804404240230,
+    0x041080200A100720,
+    0x2212900404841400,
+    0x1008007420240110,
+    0x4048010420222080,
+    0x0020800408A00066,
+    0x0008084080900803,
+    0x0406000101416000,
+    0x0202010122840440,
+    0x0004840140080800,
+    0x00084840A0012100,
+    0x0008410030041482,
+    0x94021008080080A0,
+    0x4091010100104000,
+    0x8101004402005004,
+    0x080101000A009000,
+    0x8811204143004800,
+    0x4204026000410500,
+    0x2C0208822004B002,
+    0x0610804104500400,
+    0x0000820080180082,
+    0x0004140400B01100,
+    0x10011A0200068803,
+    0x4001010204010800,
+    0x28012041000A0104,
+    0x802208020AC2C040,
+    0x0000888C20045008,
+    0x0002001404000202,
+    0x0200534202222800,
+    0x0200102010421200,
+    0x00C8300080201200,
+    0x0289880084002080,
+    0x0421010101000212,
+    0x8804144108080010,
+    0x00404210846000C0,
+    0x040103010141028A,
+    0x0800420241108980,
+    0x30002040B82A0020,
+    0x08022014194204C0,
+    0x0010042820840442,
+    0x24180A0800410A20,
+    0x0002054400941000,
+    0x0900490307292000,
+    0x4104448201008809,
+    0x0000900000420220,
+    0x400C020240050D00,
+    0x8800004004080084,
+    0x0080084830540042,
+    0x108820480C450820,
+];
+
+struct Magic {
+    mask: Bitboard,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    fn index(&self, occupancy: Bitboard) -> usize {
+        self.offset + ((occupancy & self.mask).0.wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+struct MagicTables {
+    rook: Vec<Magic>,
+    bishop: Vec<Magic>,
+    attacks: Vec<Bitboard>,
+}
+
+static MAGIC_TABLES: OnceLock<MagicTables> = OnceLock::new();
+
+fn magic_tables() -> &'static MagicTables {
+    MAGIC_TABLES.get_or_init(|| {
+        let mut attacks = Vec::new();
+        let rook = build_magics(&ROOK_MAGICS, &ROOK_DIRECTIONS, &mut attacks);
+        let bishop = build_magics(&BISHOP_MAGICS, &BISHOP_DIRECTIONS, &mut attacks);
+        MagicTables {
+            rook,
+            bishop,
+            attacks,
+        }
+    })
+}
+
+fn build_magics(
+    magics: &[u64; 64],
+    directions: &[(i8, Bitboard)],
+    attacks: &mut Vec<Bitboard>,
+) -> Vec<Magic> {
+    magics
+        .iter()
+        .enumerate()
+        .map(|(square, &magic)| {
+            let mask = relevant_occupancy(square, directions);
+            let magic = Magic {
+                mask,
+                magic,
+                shift: 64 - mask.count(),
+                offset: attacks.len(),
+            };
+            attacks.extend(vec![Bitboard::EMPTY; 1 << mask.count()]);
+            let mut subset = Bitboard::EMPTY;
+            loop {
+                attacks[magic.index(subset)] =
+                    GameState::sliding_attacks(square, subset, directions);
+                subset = Bitboard(subset.0.wrapping_sub(mask.0)) & mask;
+                if subset.is_empty() {
+                    break;
+                }
+            }
+            magic
+        })
+        .collect()
+}
+
+fn relevant_occupancy(from: usize, directions: &[(i8, Bitboard)]) -> Bitboard {
+    let step = |bit: Bitboard, shift: i8, wrapped: Bitboard| {
+        let moved = Bitboard(if shift > 0 {
+            bit.0 << shift
+        } else {
+            bit.0 >> -shift
+        });
+        moved & !wrapped
+    };
+    let mut mask = Bitboard::EMPTY;
+    for &(shift, wrapped) in directions {
+        let mut bit = step(Bitboard::from_square(from), shift, wrapped);
+        while !bit.is_empty() && !step(bit, shift, wrapped).is_empty() {
+            mask |= bit;
+            bit = step(bit, shift, wrapped);
+        }
+    }
+    mask
+}
+
+pub(crate) fn rook_attacks(from: usize, occupancy: Bitboard) -> Bitboard {
+    let tables = magic_tables();
+    tables.attacks[tables.rook[from].index(occupancy)]
+}
+
+pub(crate) fn bishop_attacks(from: usize, occupancy: Bitboard) -> Bitboard {
+    let tables = magic_tables();
+    tables.attacks[tables.bishop[from].index(occupancy)]
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Bitboard, GameState, BISHOP_DIRECTIONS, ROOK_DIRECTIONS};
+
+    #[test]
+    fn magic_attacks_match_ray_casting() {
+        let game_state = GameState::default();
+        let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+        for square in 0..64 {
+            for _ in 0..10000 {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                let occupancy = Bitboard(seed & (seed >> 3));
+                assert_eq!(
+                    GameState::sliding_attacks(square, occupancy, &ROOK_DIRECTIONS),
+                    game_state.rook_attacks(square, occupancy)
+                );
+                assert_eq!(
+                    GameState::sliding_attacks(square, occupancy, &BISHOP_DIRECTIONS),
+                    game_state.bishop_attacks(square, occupancy)
+                );
+            }
+        }
+    }
+}
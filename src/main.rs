@@ -0,0 +1,8 @@
+use chersed::UciEngine;
+
+fn main() -> std::io::Result<()> {
+    UciEngine::default().run(
+        std::io::BufReader::new(std::io::stdin()),
+        std::io::stdout().lock(),
+    )
+}
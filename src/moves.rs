@@ -0,0 +1,299 @@
+use alloc::string::{String, ToString};
+use core::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use crate::{parse_square, uci_string, Color::*, Piece, Piece::*};
+
+const PROMOTION: u16 = 0b1000;
+const CAPTURE: u16 = 0b0100;
+const CASTLE: u16 = 0b0010;
+const EN_PASSANT: u16 = CAPTURE | 0b0001;
+
+// Capture, castle and en passant are annotations set by move generation; two
+// moves are the same move whenever their squares and promotion agree.
+#[derive(Clone, Copy, Debug)]
+pub struct Move(u16);
+
+impl PartialEq for Move {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Move {}
+
+impl Hash for Move {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MoveParseError {
+    Malformed(String),
+    InvalidSquare(String),
+    InvalidPromotion(String),
+    Illegal(String),
+    Ambiguous(String),
+}
+
+impl Display for MoveParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MoveParseError::Malformed(mv) => write!(f, "malformed move {mv}"),
+            MoveParseError::InvalidSquare(mv) => write!(f, "invalid square in {mv}"),
+            MoveParseError::InvalidPromotion(mv) => write!(f, "invalid promotion in {mv}"),
+            MoveParseError::Illegal(mv) => write!(f, "{mv} is not a legal move"),
+            MoveParseError::Ambiguous(mv) => write!(f, "{mv} is ambiguous"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MoveParseError {}
+
+impl Move {
+    pub fn new(from: usize, to: usize, promotion: Option<Piece>) -> Self {
+        match Move::try_new(from, to, promotion) {
+            Ok(mv) => mv,
+            Err(_) => panic!("cannot promote to a pawn or king"),
+        }
+    }
+
+    pub fn try_new(
+        from: usize,
+        to: usize,
+        promotion: Option<Piece>,
+    ) -> Result<Self, MoveParseError> {
+        if matches!(promotion, Some(Pawn(_) | King(_))) {
+            return Err(MoveParseError::InvalidPromotion(uci_string(
+                from, to, promotion,
+            )));
+        }
+        let flags = match promotion {
+            Some(piece) => PROMOTION | (piece.index() as u16 / 2).saturating_sub(1) & 0b11,
+            None => 0,
+        };
+        Ok(Move(
+            (from as u16 & 0x3F) | (to as u16 & 0x3F) << 6 | flags << 12,
+        ))
+    }
+
+    pub fn new_capture(from: usize, to: usize, promotion: Option<Piece>) -> Self {
+        let mv = Move::new(from, to, promotion);
+        Move(mv.0 | CAPTURE << 12)
+    }
+
+    pub fn new_castle(from: usize, to: usize) -> Self {
+        Move(Move::new(from, to, None).0 | CASTLE << 12)
+    }
+
+    pub fn new_en_passant(from: usize, to: usize) -> Self {
+        Move(Move::new(from, to, None).0 | EN_PASSANT << 12)
+    }
+
+    pub fn from_bits(bits: u16) -> Self {
+        Move(bits)
+    }
+
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn from(&self) -> usize {
+        (self.0 & 0x3F) as usize
+    }
+
+    pub fn to(&self) -> usize {
+        (self.0 >> 6 & 0x3F) as usize
+    }
+
+    pub fn flags(&self) -> u8 {
+        (self.0 >> 12) as u8
+    }
+
+    pub fn is_capture(&self) -> bool {
+        self.flags() as u16 & CAPTURE != 0
+    }
+
+    pub fn is_castle(&self) -> bool {
+        self.flags() as u16 == CASTLE
+    }
+
+    pub fn is_en_passant(&self) -> bool {
+        self.flags() as u16 == EN_PASSANT
+    }
+
+    pub fn is_promotion(&self) -> bool {
+        self.flags() as u16 & PROMOTION != 0
+    }
+
+    fn key(&self) -> u16 {
+        if self.is_promotion() {
+            self.0 & !(CAPTURE << 12)
+        } else {
+            self.0 & 0x0FFF
+        }
+    }
+
+    pub fn promotion(&self) -> Option<Piece> {
+        let flags = self.0 >> 12;
+        if flags & PROMOTION == 0 {
+            return None;
+        }
+        let color = if self.to() / 8 == 0 { Black } else { White };
+        Piece::from_index(((flags & 0b11) as usize + 1) * 2 + color.index())
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}",
+            uci_string(self.from(), self.to(), self.promotion())
+        )
+    }
+}
+
+impl FromStr for Move {
+    type Err = MoveParseError;
+
+    fn from_str(uci: &str) -> Result<Self, Self::Err> {
+        if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+            return Err(MoveParseError::Malformed(uci.to_string()));
+        }
+        let invalid_square = || MoveParseError::InvalidSquare(uci.to_string());
+        let from = parse_square(&uci[0..2]).ok_or_else(invalid_square)?;
+        let to = parse_square(&uci[2..4]).ok_or_else(invalid_square)?;
+        let color = if to / 8 == 0 { Black } else { White };
+        let promotion = match uci[4..].chars().next() {
+            None => None,
+            Some('q') => Some(Queen(color)),
+            Some('r') => Some(Rook(color)),
+            Some('b') => Some(Bishop(color)),
+            Some('n') => Some(Knight(color)),
+            Some(_) => return Err(MoveParseError::InvalidPromotion(uci.to_string())),
+        };
+        Ok(Move::new(from, to, promotion))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use crate::{Color::*, GameState, Move, MoveParseError, Piece::*};
+
+    #[test]
+    fn packed_round_trip() {
+        for (from, to, promotion) in [
+            (12, 28, None),
+            (52, 60, Some(Queen(White))),
+            (50, 59, Some(Knight(White))),
+            (9, 0, Some(Rook(Black))),
+            (14, 7, Some(Bishop(Black))),
+            (63, 0, None),
+        ] {
+            let mv = Move::new(from, to, promotion);
+            assert_eq!(from, mv.from());
+            assert_eq!(to, mv.to());
+            assert_eq!(promotion, mv.promotion());
+            assert_eq!(mv, Move::from_bits(mv.bits()));
+        }
+        assert_eq!(0, Move::new(12, 28, None).flags());
+        assert_eq!(0b1011, Move::new(52, 60, Some(Queen(White))).flags());
+        assert_eq!(2, core::mem::size_of::<Move>());
+    }
+
+    #[test]
+    fn special_move_flags() {
+        let capture = Move::new_capture(28, 35, None);
+        assert!(capture.is_capture() && !capture.is_en_passant() && !capture.is_castle());
+        let en_passant = Move::new_en_passant(36, 43);
+        assert!(en_passant.is_capture() && en_passant.is_en_passant());
+        let castle = Move::new_castle(4, 6);
+        assert!(castle.is_castle() && !castle.is_capture());
+        let promotion = Move::new_capture(50, 59, Some(Queen(White)));
+        assert!(promotion.is_capture() && promotion.is_promotion());
+        assert_eq!(Some(Queen(White)), promotion.promotion());
+        assert_eq!(0b1111, promotion.flags());
+        let quiet = Move::new(12, 28, None);
+        assert!(!quiet.is_capture() && !quiet.is_castle() && !quiet.is_promotion());
+        for mv in [capture, en_passant, castle, promotion] {
+            assert_eq!(mv, Move::from_bits(mv.bits()));
+            assert_eq!(mv.bits(), Move::from_bits(mv.bits()).bits());
+        }
+    }
+
+    #[test]
+    fn flags_do_not_affect_equality() {
+        use std::collections::HashSet;
+
+        assert_eq!(Move::new(28, 35, None), Move::new_capture(28, 35, None));
+        assert_eq!(Move::new(4, 6, None), Move::new_castle(4, 6));
+        assert_eq!(Move::new(36, 43, None), Move::new_en_passant(36, 43));
+        assert_eq!(
+            Move::new(50, 59, Some(Knight(White))),
+            Move::new_capture(50, 59, Some(Knight(White)))
+        );
+        assert_ne!(
+            Move::new(50, 59, Some(Knight(White))),
+            Move::new_capture(50, 59, Some(Queen(White)))
+        );
+        let moves: HashSet<Move> = [Move::new(4, 6, None), Move::new_castle(4, 6)].into();
+        assert_eq!(1, moves.len());
+    }
+
+    #[test]
+    fn pawn_and_king_promotions_are_rejected() {
+        assert_eq!(
+            Err(MoveParseError::InvalidPromotion("e7e8k".to_string())),
+            Move::try_new(52, 60, Some(King(White)))
+        );
+        assert_eq!(
+            Err(MoveParseError::InvalidPromotion("e2e1p".to_string())),
+            Move::try_new(12, 4, Some(Pawn(Black)))
+        );
+        assert_eq!(
+            Ok(Move::new(52, 60, Some(Rook(White)))),
+            Move::try_new(52, 60, Some(Rook(White)))
+        );
+    }
+
+    // Must hold in release builds too, where debug assertions are compiled out.
+    #[test]
+    #[should_panic(expected = "cannot promote to a pawn or king")]
+    fn new_panics_on_king_promotion() {
+        Move::new(52, 60, Some(King(White)));
+    }
+
+    #[test]
+    fn uci_round_trip() {
+        let game_state = "3qk3/2P5/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        for mv in game_state.legal_moves() {
+            assert_eq!(Ok(mv), game_state.parse_uci(&mv.to_string()));
+        }
+        let mv = Move::new(50, 59, Some(Knight(White)));
+        assert_eq!("c7d8n", mv.to_string());
+        assert_eq!(Ok(mv), "c7d8n".parse());
+        assert_eq!(Ok(Move::new(8, 0, Some(Queen(Black)))), "a2a1q".parse());
+        assert_eq!(
+            Err(MoveParseError::InvalidPromotion("e7e8k".to_string())),
+            "e7e8k".parse::<Move>()
+        );
+        assert_eq!(
+            Err(MoveParseError::InvalidSquare("e9e8".to_string())),
+            "e9e8".parse::<Move>()
+        );
+        assert_eq!(
+            Err(MoveParseError::Malformed("e2".to_string())),
+            "e2".parse::<Move>()
+        );
+    }
+}
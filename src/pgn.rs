@@ -0,0 +1,536 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use core::fmt::Display;
+
+use crate::{Color::*, FenError, Game, GameState, Move, MoveParseError};
+
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+const LINE_WIDTH: usize = 80;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PgnError {
+    UnterminatedComment,
+    UnterminatedVariation,
+    UnbalancedVariation,
+    MalformedTag(String),
+    InvalidFen(FenError),
+    InvalidMove { index: usize, error: MoveParseError },
+}
+
+impl Display for PgnError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PgnError::UnterminatedComment => write!(f, "unterminated comment"),
+            PgnError::UnterminatedVariation => write!(f, "unterminated variation"),
+            PgnError::UnbalancedVariation => write!(f, "unbalanced variation"),
+            PgnError::MalformedTag(line) => write!(f, "malformed tag pair {line}"),
+            PgnError::InvalidFen(err) => write!(f, "invalid FEN tag: {err}"),
+            PgnError::InvalidMove { index, error } => write!(f, "after {index} moves: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PgnError {}
+
+enum Token<'a> {
+    Open,
+    Close,
+    Word(&'a str),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token<'_>>, PgnError> {
+    let mut tokens = Vec::new();
+    let mut variation_depth = 0;
+    let mut rest = text;
+    while let Some(char) = rest.chars().next() {
+        match char {
+            '{' => {
+                let end = rest.find('}').ok_or(PgnError::UnterminatedComment)?;
+                rest = &rest[end + 1..];
+                continue;
+            }
+            ';' => {
+                rest = rest.find('\n').map_or("", |end| &rest[end..]);
+                continue;
+            }
+            '(' => {
+                variation_depth += 1;
+                tokens.push(Token::Open);
+            }
+            ')' if variation_depth == 0 => return Err(PgnError::UnbalancedVariation),
+            ')' => {
+                variation_depth -= 1;
+                tokens.push(Token::Close);
+            }
+            _ if char.is_whitespace() => {}
+            _ => {
+                let end = rest
+                    .find(|char: char| char.is_whitespace() || "{;()".contains(char))
+                    .unwrap_or(rest.len());
+                tokens.push(Token::Word(&rest[..end]));
+                rest = &rest[end..];
+                continue;
+            }
+        }
+        rest = &rest[char.len_utf8()..];
+    }
+    if variation_depth > 0 {
+        return Err(PgnError::UnterminatedVariation);
+    }
+    Ok(tokens)
+}
+
+// A variation replaces the move at index `ply` of the line it is attached to,
+// and starts from the position before that move.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PgnVariation {
+    pub ply: usize,
+    pub moves: Vec<Move>,
+    pub variations: Vec<PgnVariation>,
+}
+
+#[derive(Default)]
+struct Line {
+    moves: Vec<Move>,
+    variations: Vec<PgnVariation>,
+    result: Option<&'static str>,
+}
+
+fn parse_line<'a>(
+    tokens: &mut impl Iterator<Item = &'a Token<'a>>,
+    start: &GameState,
+    first_index: usize,
+) -> Result<Line, PgnError> {
+    let mut line = Line::default();
+    let mut game_state = start.clone();
+    let mut previous: Option<GameState> = None;
+    while let Some(token) = tokens.next() {
+        let word = match token {
+            Token::Open => {
+                let before = previous.as_ref().ok_or(PgnError::UnbalancedVariation)?;
+                let ply = line.moves.len() - 1;
+                let variation = parse_line(tokens, before, first_index + ply)?;
+                line.variations.push(PgnVariation {
+                    ply,
+                    moves: variation.moves,
+                    variations: variation.variations,
+                });
+                continue;
+            }
+            Token::Close => break,
+            Token::Word(word) => *word,
+        };
+        if let Some(result) = RESULTS.iter().find(|&&result| result == word) {
+            line.result = Some(result);
+            break;
+        }
+        let san = word
+            .rsplit_once('.')
+            .filter(|(number, _)| {
+                number
+                    .trim_end_matches('.')
+                    .bytes()
+                    .all(|byte| byte.is_ascii_digit())
+            })
+            .map_or(word, |(_, san)| san);
+        if san.is_empty() || san.starts_with('$') {
+            continue;
+        }
+        let mv = game_state
+            .parse_san(san)
+            .map_err(|error| PgnError::InvalidMove {
+                index: first_index + line.moves.len(),
+                error,
+            })?;
+        previous = Some(game_state.clone());
+        game_state
+            .make_move(mv)
+            .expect("moves parsed from SAN are legal");
+        line.moves.push(mv);
+    }
+    Ok(line)
+}
+
+fn parse_movetext(start: &GameState, text: &str) -> Result<Line, PgnError> {
+    let tokens = tokenize(text)?;
+    parse_line(&mut tokens.iter(), start, 0)
+}
+
+pub fn parse_pgn_movetext(start: &GameState, text: &str) -> Result<Vec<GameState>, PgnError> {
+    let mut game_state = start.clone();
+    parse_movetext(start, text)?
+        .moves
+        .into_iter()
+        .map(|mv| {
+            game_state
+                .make_move(mv)
+                .expect("moves parsed from SAN are legal");
+            Ok(game_state.clone())
+        })
+        .collect()
+}
+
+fn parse_tag(line: &str) -> Result<(String, String), PgnError> {
+    let (name, value) = line
+        .trim()
+        .strip_prefix('[')
+        .and_then(|tag| tag.strip_suffix(']'))
+        .and_then(|tag| tag.split_once(' '))
+        .ok_or_else(|| PgnError::MalformedTag(line.to_string()))?;
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or_else(|| PgnError::MalformedTag(line.to_string()))?;
+    Ok((
+        name.to_string(),
+        value.replace("\\\"", "\"").replace("\\\\", "\\"),
+    ))
+}
+
+#[derive(Clone, Debug)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub game: Game,
+    pub variations: Vec<PgnVariation>,
+    pub result: String,
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn parse(tags: Vec<(String, String)>, movetext: &str) -> Result<PgnGame, PgnError> {
+        let start = match tags.iter().find(|(name, _)| name == "FEN") {
+            Some((_, fen)) => fen.parse::<GameState>().map_err(PgnError::InvalidFen)?,
+            None => GameState::default(),
+        };
+        let mut game = Game::new(start.clone());
+        let line = parse_movetext(&start, movetext)?;
+        for mv in line.moves {
+            game.make_move(mv).expect("moves parsed from SAN are legal");
+        }
+        let result = line
+            .result
+            .map(str::to_string)
+            .or_else(|| {
+                tags.iter()
+                    .find(|(name, _)| name == "Result")
+                    .map(|(_, result)| result.clone())
+            })
+            .unwrap_or("*".to_string());
+        Ok(PgnGame {
+            tags,
+            game,
+            variations: line.variations,
+            result,
+        })
+    }
+}
+
+impl Display for PgnGame {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{name} \"{value}\"]")?;
+        }
+        writeln!(f)?;
+        let mut tokens = Vec::new();
+        push_line(
+            &mut tokens,
+            self.game.start(),
+            self.game.moves(),
+            &self.variations,
+        );
+        tokens.push(self.result.clone());
+        writeln!(f, "{}", wrap(tokens))
+    }
+}
+
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut games = Vec::new();
+    let mut tags = Vec::new();
+    let mut movetext = String::new();
+    for line in text.lines() {
+        if line.trim_start().starts_with('[') {
+            if !movetext.trim().is_empty() {
+                games.push(PgnGame::parse(core::mem::take(&mut tags), &movetext)?);
+                movetext.clear();
+            }
+            tags.push(parse_tag(line)?);
+        } else {
+            movetext.push_str(line);
+            movetext.push('\n');
+        }
+    }
+    if !tags.is_empty() || !movetext.trim().is_empty() {
+        games.push(PgnGame::parse(tags, &movetext)?);
+    }
+    Ok(games)
+}
+
+fn push_line(
+    tokens: &mut Vec<String>,
+    start: &GameState,
+    moves: &[Move],
+    variations: &[PgnVariation],
+) {
+    let mut game_state = start.clone();
+    let mut numbered = false;
+    for (index, &mv) in moves.iter().enumerate() {
+        let san = game_state.move_to_san(mv);
+        let number = game_state.full_move_number;
+        match game_state.active_color {
+            White => tokens.push(format!("{number}. {san}")),
+            Black if !numbered => tokens.push(format!("{number}... {san}")),
+            Black => tokens.push(san),
+        }
+        numbered = true;
+        for variation in variations.iter().filter(|variation| variation.ply == index) {
+            let mut nested = Vec::new();
+            push_line(
+                &mut nested,
+                &game_state,
+                &variation.moves,
+                &variation.variations,
+            );
+            let Some(last) = nested.last_mut() else {
+                continue;
+            };
+            last.push(')');
+            nested[0].insert(0, '(');
+            tokens.append(&mut nested);
+            numbered = false;
+        }
+        if game_state.make_move(mv).is_err() {
+            break;
+        }
+    }
+}
+
+fn wrap(tokens: Vec<String>) -> String {
+    let mut pgn = String::new();
+    let mut line_length = 0;
+    for token in tokens {
+        if line_length > 0 && line_length + 1 + token.len() > LINE_WIDTH {
+            pgn.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            pgn.push(' ');
+            line_length += 1;
+        }
+        line_length += token.len();
+        pgn.push_str(&token);
+    }
+    pgn
+}
+
+impl GameState {
+    pub fn to_pgn(start: &GameState, moves: &[Move], result: &str) -> String {
+        let mut tokens = Vec::new();
+        push_line(&mut tokens, start, moves, &[]);
+        tokens.push(result.to_string());
+        wrap(tokens)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use crate::{
+        parse_pgn, parse_pgn_movetext, Color::*, GameResult, GameState, Move, MoveParseError,
+        PgnError, PgnVariation,
+    };
+
+    #[test]
+    fn parse_annotated_movetext() {
+        let positions = parse_pgn_movetext(
+            &GameState::default(),
+            "1. e4 {King's pawn} e5 2. Nf3 $1 Nc6 (2... d6 3. d4) 3.Bb5 a6!? ; Morphy\n4. Ba4 1/2-1/2",
+        )
+        .unwrap();
+        assert_eq!(7, positions.len());
+        assert_eq!(
+            "r1bqkbnr/1ppp1ppp/p1n5/4p3/B3P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 1 4",
+            positions.last().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn parse_movetext_errors() {
+        let start = GameState::default();
+        assert_eq!(
+            Err(PgnError::InvalidMove {
+                index: 2,
+                error: MoveParseError::Illegal("Ke3".to_string())
+            }),
+            parse_pgn_movetext(&start, "1. e4 e5 2. Ke3")
+        );
+        assert_eq!(
+            Err(PgnError::UnterminatedComment),
+            parse_pgn_movetext(&start, "1. e4 {unterminated")
+        );
+        assert_eq!(
+            Err(PgnError::UnterminatedVariation),
+            parse_pgn_movetext(&start, "1. e4 (1. d4")
+        );
+        assert!(matches!(
+            parse_pgn("[FEN \"8/8 w - - 0 1\"]\n\n*"),
+            Err(PgnError::InvalidFen(_))
+        ));
+        assert!(matches!(
+            parse_pgn("[Event Casual]\n\n*"),
+            Err(PgnError::MalformedTag(_))
+        ));
+        assert_eq!(Ok(vec![]), parse_pgn_movetext(&start, "*"));
+        let castled = parse_pgn_movetext(&start, "1.e4 e5 2.Nf3 Nf6 3.Bc4 Bc5 4.0-0 1-0").unwrap();
+        assert_eq!(7, castled.len());
+    }
+
+    #[test]
+    fn to_pgn_numbering() {
+        let start = GameState::default();
+        let moves = [
+            Move::new(12, 28, None),
+            Move::new(52, 36, None),
+            Move::new(6, 21, None),
+            Move::new(57, 42, None),
+            Move::new(5, 33, None),
+        ];
+        assert_eq!(
+            "1. e4 e5 2. Nf3 Nc6 3. Bb5 *",
+            GameState::to_pgn(&start, &moves, "*")
+        );
+        let after_e4 = start.play_move(moves[0]).unwrap();
+        assert_eq!(
+            "1... e5 2. Nf3 1-0",
+            GameState::to_pgn(&after_e4, &moves[1..3], "1-0")
+        );
+    }
+
+    #[test]
+    fn to_pgn_wraps_and_round_trips() {
+        let start = GameState::default();
+        let moves = [
+            Move::new(6, 21, None),
+            Move::new(62, 45, None),
+            Move::new(21, 6, None),
+            Move::new(45, 62, None),
+        ]
+        .repeat(10);
+        let pgn = GameState::to_pgn(&start, &moves, "1/2-1/2");
+        assert!(pgn.lines().all(|line| line.len() <= 80), "{pgn}");
+        assert!(pgn.lines().count() > 1);
+        let positions = parse_pgn_movetext(&start, &pgn).unwrap();
+        assert_eq!(40, positions.len());
+        assert_eq!(
+            start.to_string().replace(" 0 1", " 40 21"),
+            positions[39].to_string()
+        );
+    }
+
+    #[test]
+    fn parse_multi_game_pgn() {
+        let pgn = r#"[Event "Casual"]
+[White "Legall"]
+[Black "Saint Brie"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 d6 3. Bc4 Bg4 4. Nc3 g6 (4... Nf6) 5. Nxe5 Bxd1
+6. Bxf7+ Ke7 7. Nd5# 1-0
+
+[Event "Endgame"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"]
+
+1. e4 Kd7 {drawn} *
+"#;
+        let games = parse_pgn(pgn).unwrap();
+        assert_eq!(2, games.len());
+        assert_eq!(Some("Legall"), games[0].tag("White"));
+        assert_eq!("1-0", games[0].result);
+        assert_eq!(13, games[0].game.moves().len());
+        assert_eq!(
+            GameResult::Checkmate(White),
+            games[0].game.position().result()
+        );
+        assert_eq!(
+            "4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1",
+            games[1].game.positions()[1].to_string()
+        );
+        assert_eq!("*", games[1].result);
+
+        let exported = games[0].to_string();
+        assert!(exported.starts_with("[Event \"Casual\"]\n[White \"Legall\"]"));
+        let reparsed = parse_pgn(&exported).unwrap();
+        assert_eq!(games[0].game.moves(), reparsed[0].game.moves());
+        assert_eq!(games[0].tags, reparsed[0].tags);
+    }
+
+    #[test]
+    fn parse_nested_variations() {
+        let pgn = "1. e4 e5 (1... c5 2. Nf3 (2. Nc3 Nc6) 2... d6) (1... e6) 2. Nf3 *";
+        let games = parse_pgn(pgn).unwrap();
+        let san = |uci: &str| uci.parse::<Move>().unwrap();
+        assert_eq!(
+            &[san("e2e4"), san("e7e5"), san("g1f3")],
+            games[0].game.moves()
+        );
+        assert_eq!(
+            vec![
+                PgnVariation {
+                    ply: 1,
+                    moves: vec![san("c7c5"), san("g1f3"), san("d7d6")],
+                    variations: vec![PgnVariation {
+                        ply: 1,
+                        moves: vec![san("b1c3"), san("b8c6")],
+                        variations: vec![],
+                    }],
+                },
+                PgnVariation {
+                    ply: 1,
+                    moves: vec![san("e7e6")],
+                    variations: vec![],
+                },
+            ],
+            games[0].variations
+        );
+        assert_eq!(format!("\n{pgn}\n"), games[0].to_string());
+        let white_variation = parse_pgn("1. e4 (1. d4 d5) 1... e5 *").unwrap();
+        assert_eq!(1, white_variation[0].variations.len());
+        assert_eq!(0, white_variation[0].variations[0].ply);
+        assert_eq!(
+            "\n1. e4 (1. d4 d5) 1... e5 *\n",
+            white_variation[0].to_string()
+        );
+    }
+
+    #[test]
+    fn variation_errors() {
+        let start = GameState::default();
+        assert_eq!(
+            Err(PgnError::InvalidMove {
+                index: 4,
+                error: MoveParseError::Illegal("Nf6".to_string())
+            }),
+            parse_pgn_movetext(&start, "1. e4 e5 2. Nf3 (2. d4 Nf6 3. Nf6) Nc6")
+        );
+        assert_eq!(
+            Err(PgnError::UnbalancedVariation),
+            parse_pgn_movetext(&start, "1. e4 e5) 2. Nf3")
+        );
+        assert_eq!(
+            Err(PgnError::UnbalancedVariation),
+            parse_pgn_movetext(&start, "(1. d4) 1. e4")
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use rand::Rng;
+
+use crate::{Bitboard, Color::*, GameState, Piece, Piece::*, KING_ATTACKS, RANKS};
+
+impl GameState {
+    pub fn random(rng: &mut impl Rng) -> GameState {
+        loop {
+            let mut game_state = GameState {
+                bitboards: [Bitboard::EMPTY; 12],
+                occupancy: [Bitboard::EMPTY; 2],
+                active_color: if rng.gen() { White } else { Black },
+                castling_rights: [false; 4],
+                ..GameState::default()
+            };
+            let white_king = rng.gen_range(0..64);
+            let black_king = loop {
+                let square = rng.gen_range(0..64);
+                if square != white_king && !KING_ATTACKS[white_king].contains(square) {
+                    break square;
+                }
+            };
+            game_state.put_piece(King(White), white_king);
+            game_state.put_piece(King(Black), black_king);
+            for _ in 0..rng.gen_range(0..=24) {
+                let color = if rng.gen() { White } else { Black };
+                let Some(piece) = Piece::from_index(rng.gen_range(0..5) * 2 + color.index()) else {
+                    continue;
+                };
+                let square = rng.gen_range(0..64);
+                if game_state.all_occupancy().contains(square)
+                    || (matches!(piece, Pawn(_)) && (RANKS[0] | RANKS[7]).contains(square))
+                {
+                    continue;
+                }
+                game_state.put_piece(piece, square);
+            }
+            if game_state.validate().is_ok() {
+                return game_state;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    use crate::GameState;
+
+    #[test]
+    fn random_positions_round_trip_through_fen() {
+        let mut rng = SmallRng::seed_from_u64(0x4348_4552_5345_4421);
+        for _ in 0..1000 {
+            let game_state = GameState::random(&mut rng);
+            assert_eq!(Ok(()), game_state.validate());
+            let fen = game_state.to_string();
+            assert_eq!(Ok(game_state), fen.parse::<GameState>(), "{fen}");
+        }
+    }
+}
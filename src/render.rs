@@ -0,0 +1,82 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{Color, Color::*, GameState, Piece, RANK_MATRIX};
+
+impl GameState {
+    pub fn to_ascii(&self) -> String {
+        self.render(White, |piece| piece.fen())
+    }
+
+    pub fn to_unicode(&self, perspective: Color) -> String {
+        self.render(perspective, |piece| piece.unicode().to_string())
+    }
+
+    fn render(&self, perspective: Color, glyph: impl Fn(Piece) -> String) -> String {
+        let board_state = self.get_board_state();
+        let (ranks, files): (Vec<usize>, Vec<usize>) = match perspective {
+            White => ((0..8).rev().collect(), (0..8).collect()),
+            Black => ((0..8).collect(), (0..8).rev().collect()),
+        };
+        let mut rendered = String::new();
+        for &rank in ranks.iter() {
+            rendered.push_str(&(rank + 1).to_string());
+            for &file in files.iter() {
+                rendered.push(' ');
+                match board_state[rank][file] {
+                    Some(piece) => rendered.push_str(&glyph(piece)),
+                    None => rendered.push('.'),
+                }
+            }
+            rendered.push('\n');
+        }
+        rendered.push(' ');
+        for &file in files.iter() {
+            rendered.push(' ');
+            rendered.push_str(RANK_MATRIX[file]);
+        }
+        rendered.push('\n');
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Color::*, GameState, Piece::*};
+
+    #[test]
+    fn ascii_default_position() {
+        let expected = "\
+8 r n b q k b n r
+7 p p p p p p p p
+6 . . . . . . . .
+5 . . . . . . . .
+4 . . . . . . . .
+3 . . . . . . . .
+2 P P P P P P P P
+1 R N B Q K B N R
+  a b c d e f g h
+";
+        assert_eq!(expected, GameState::default().to_ascii());
+    }
+
+    #[test]
+    fn unicode_perspectives() {
+        let game_state = GameState::default();
+        let white = game_state.to_unicode(White);
+        assert_eq!(Some("1 ♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖"), white.lines().nth(7));
+        assert_eq!(Some("8 ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜"), white.lines().next());
+        let black = game_state.to_unicode(Black);
+        assert_eq!(Some("1 ♖ ♘ ♗ ♔ ♕ ♗ ♘ ♖"), black.lines().next());
+        assert_eq!(Some("  h g f e d c b a"), black.lines().last());
+        assert!(white.lines().all(|line| line.chars().count() == 17));
+    }
+
+    #[test]
+    fn unicode_pieces() {
+        assert_eq!('♔', King(White).unicode());
+        assert_eq!('♟', Pawn(Black).unicode());
+    }
+}
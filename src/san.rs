@@ -0,0 +1,336 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    parse_square, square_name, Color, GameState, Move, MoveParseError, Piece, Piece::*, RANK_MATRIX,
+};
+
+fn piece_from_letter(letter: char, color: Color) -> Option<Piece> {
+    match letter {
+        'N' => Some(Knight(color)),
+        'B' => Some(Bishop(color)),
+        'R' => Some(Rook(color)),
+        'Q' => Some(Queen(color)),
+        'K' => Some(King(color)),
+        _ => None,
+    }
+}
+
+impl GameState {
+    pub fn parse_san(&self, san: &str) -> Result<Move, MoveParseError> {
+        let color = self.active_color;
+        let stripped = san.trim_end_matches(['+', '#', '!', '?']);
+        let legal_moves = self.legal_moves();
+
+        if matches!(stripped, "O-O" | "0-0" | "O-O-O" | "0-0-0") {
+            let king_to_file = if stripped.len() == 3 { 6 } else { 2 };
+            return legal_moves
+                .into_iter()
+                .find(|&mv| {
+                    self.castling_for(King(color), mv.from(), mv.to())
+                        .is_some_and(|castling| castling.king_to % 8 == king_to_file)
+                })
+                .ok_or_else(|| MoveParseError::Illegal(san.to_string()));
+        }
+
+        let (body, promotion) = match stripped.split_once('=') {
+            Some((body, promotion)) => {
+                let mut chars = promotion.chars();
+                let piece = chars
+                    .next()
+                    .and_then(|letter| piece_from_letter(letter, color))
+                    .filter(|piece| !matches!(piece, King(_)))
+                    .ok_or_else(|| MoveParseError::InvalidPromotion(san.to_string()))?;
+                if chars.next().is_some() {
+                    return Err(MoveParseError::InvalidPromotion(san.to_string()));
+                }
+                (body, Some(piece))
+            }
+            None => (stripped, None),
+        };
+
+        let mut chars = body.chars().peekable();
+        let piece = match chars
+            .peek()
+            .and_then(|&letter| piece_from_letter(letter, color))
+        {
+            Some(piece) => {
+                chars.next();
+                piece
+            }
+            None => Pawn(color),
+        };
+        let rest: String = chars.filter(|&char| char != 'x').collect();
+        if rest.len() < 2 {
+            return Err(MoveParseError::Malformed(san.to_string()));
+        }
+        let (disambiguation, destination) = rest.split_at(rest.len() - 2);
+        let to = parse_square(destination)
+            .ok_or_else(|| MoveParseError::InvalidSquare(san.to_string()))?;
+        let mut from_file = None;
+        let mut from_rank = None;
+        for char in disambiguation.chars() {
+            match char {
+                'a'..='h' if from_file.is_none() => from_file = Some(char as usize - 'a' as usize),
+                '1'..='8' if from_rank.is_none() => from_rank = Some(char as usize - '1' as usize),
+                _ => return Err(MoveParseError::Malformed(san.to_string())),
+            }
+        }
+
+        let candidates: Vec<Move> = legal_moves
+            .into_iter()
+            .filter(|&mv| {
+                mv.to() == to
+                    && mv.promotion() == promotion
+                    && self.get_piece_on(mv.from()) == Some(piece)
+                    && from_file.is_none_or(|file| mv.from() % 8 == file)
+                    && from_rank.is_none_or(|rank| mv.from() / 8 == rank)
+            })
+            .collect();
+        match candidates.as_slice() {
+            [mv] => Ok(*mv),
+            [] => Err(MoveParseError::Illegal(san.to_string())),
+            _ => Err(MoveParseError::Ambiguous(san.to_string())),
+        }
+    }
+
+    pub fn move_to_san(&self, mv: Move) -> String {
+        let (from, to, promotion) = (mv.from(), mv.to(), mv.promotion());
+        let Some(piece) = self.get_piece_on(from) else {
+            return String::new();
+        };
+        let mut san = if let Some(castling) = self.castling_for(piece, from, to) {
+            if castling.king_to % 8 == 6 {
+                "O-O".to_string()
+            } else {
+                "O-O-O".to_string()
+            }
+        } else {
+            let is_capture = self.move_flags(from, to).is_capture;
+            let mut san = String::new();
+            if matches!(piece, Pawn(_)) {
+                if is_capture {
+                    san.push_str(RANK_MATRIX[from % 8]);
+                }
+            } else {
+                san.push_str(&piece.fen().to_uppercase());
+                let rivals: Vec<usize> = self
+                    .legal_moves()
+                    .into_iter()
+                    .filter(|other| {
+                        other.from() != from
+                            && other.to() == to
+                            && self.get_piece_on(other.from()) == Some(piece)
+                    })
+                    .map(|other| other.from())
+                    .collect();
+                if !rivals.is_empty() {
+                    if rivals.iter().all(|other| other % 8 != from % 8) {
+                        san.push_str(RANK_MATRIX[from % 8]);
+                    } else if rivals.iter().all(|other| other / 8 != from / 8) {
+                        san.push_str(&(from / 8 + 1).to_string());
+                    } else {
+                        san.push_str(&square_name(from));
+                    }
+                }
+            }
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&square_name(to));
+            if let Some(promotion) = promotion {
+                san.push('=');
+                san.push_str(&promotion.fen().to_uppercase());
+            }
+            san
+        };
+        if let Ok(next) = self.play_move(mv) {
+            if next.is_in_check(next.active_color) {
+                san.push(if next.legal_moves_iter().next().is_none() {
+                    '#'
+                } else {
+                    '+'
+                });
+            }
+        }
+        san
+    }
+
+    pub fn san_line(&self, moves: &[Move]) -> Result<Vec<String>, MoveParseError> {
+        let mut scratch = self.clone();
+        let mut line = Vec::with_capacity(moves.len());
+        for &mv in moves {
+            if !scratch.legal_moves_iter().any(|legal| legal == mv) {
+                return Err(MoveParseError::Illegal(mv.to_string()));
+            }
+            line.push(scratch.move_to_san(mv));
+            scratch
+                .make_move(mv)
+                .map_err(|_| MoveParseError::Illegal(mv.to_string()))?;
+        }
+        Ok(line)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use crate::{Color::*, GameState, Move, MoveParseError, Piece::*};
+
+    #[test]
+    fn parse_san_piece_and_pawn_moves() {
+        let game_state = GameState::default();
+        assert_eq!(Ok(Move::new(6, 21, None)), game_state.parse_san("Nf3"));
+        assert_eq!(Ok(Move::new(12, 28, None)), game_state.parse_san("e4"));
+        assert!(game_state.parse_san("e5").is_err());
+        assert_eq!(
+            Err(MoveParseError::Illegal("Nd4".to_string())),
+            game_state.parse_san("Nd4")
+        );
+    }
+
+    #[test]
+    fn parse_san_capture() {
+        let game_state = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(Ok(Move::new(28, 35, None)), game_state.parse_san("exd5"));
+    }
+
+    #[test]
+    fn parse_san_castling() {
+        let game_state = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(Ok(Move::new(4, 6, None)), game_state.parse_san("O-O"));
+        assert_eq!(Ok(Move::new(4, 2, None)), game_state.parse_san("O-O-O+"));
+    }
+
+    #[test]
+    fn parse_san_promotion() {
+        let game_state = "3qk3/2P5/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            Ok(Move::new(50, 58, Some(Queen(White)))),
+            game_state.parse_san("c8=Q+")
+        );
+        assert_eq!(
+            Ok(Move::new(50, 59, Some(Knight(White)))),
+            game_state.parse_san("cxd8=N")
+        );
+        assert!(game_state.parse_san("c8").is_err());
+        assert_eq!(
+            Err(MoveParseError::InvalidPromotion("c8=K".to_string())),
+            game_state.parse_san("c8=K")
+        );
+    }
+
+    #[test]
+    fn parse_san_disambiguation() {
+        let game_state = "4k3/8/8/8/8/R7/8/RN2KN2 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(Ok(Move::new(1, 11, None)), game_state.parse_san("Nbd2"));
+        assert_eq!(Ok(Move::new(5, 11, None)), game_state.parse_san("Nfd2"));
+        assert_eq!(Ok(Move::new(0, 8, None)), game_state.parse_san("R1a2"));
+        assert_eq!(
+            Err(MoveParseError::Ambiguous("Ra2".to_string())),
+            game_state.parse_san("Ra2")
+        );
+        assert!(game_state.parse_san("Nd2").is_err());
+    }
+
+    #[test]
+    fn move_to_san_disambiguation() {
+        let game_state = "4k3/8/8/8/8/R7/8/RN2KN2 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!("Nbd2", game_state.move_to_san(Move::new(1, 11, None)));
+        assert_eq!("Nfd2", game_state.move_to_san(Move::new(5, 11, None)));
+        assert_eq!("Ng3", game_state.move_to_san(Move::new(5, 22, None)));
+        assert_eq!("R1a2", game_state.move_to_san(Move::new(0, 8, None)));
+        let game_state = "4k3/8/8/8/8/8/8/N1N1K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!("Nab3", game_state.move_to_san(Move::new(0, 17, None)));
+    }
+
+    #[test]
+    fn move_to_san_pawns() {
+        let game_state = GameState::default();
+        assert_eq!("e4", game_state.move_to_san(Move::new(12, 28, None)));
+        let game_state = "3qk3/2P5/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            "cxd8=Q+",
+            game_state.move_to_san(Move::new(50, 59, Some(Queen(White))))
+        );
+        assert_eq!(
+            "c8=N",
+            game_state.move_to_san(Move::new(50, 58, Some(Knight(White))))
+        );
+        let game_state = "4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 2"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!("dxe6", game_state.move_to_san(Move::new(35, 44, None)));
+    }
+
+    #[test]
+    fn move_to_san_castling_and_mate() {
+        let game_state = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!("O-O", game_state.move_to_san(Move::new(4, 6, None)));
+        assert_eq!("O-O-O", game_state.move_to_san(Move::new(4, 2, None)));
+        let game_state = "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!("Ra8#", game_state.move_to_san(Move::new(0, 56, None)));
+    }
+
+    #[test]
+    fn move_to_san_round_trips_through_parse_san() {
+        let game_state = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        for mv in game_state.legal_moves() {
+            let san = game_state.move_to_san(mv);
+            assert_eq!(Ok(mv), game_state.parse_san(&san), "{san}");
+        }
+    }
+
+    #[test]
+    fn move_to_san_suffixes() {
+        let game_state = GameState::default()
+            .play_moves(&["e4", "e5", "Qh5", "Nc6", "Bc4", "Nf6"])
+            .unwrap();
+        let mate = game_state.parse_san("Qxf7").unwrap();
+        assert_eq!("Qxf7#", game_state.move_to_san(mate));
+        let game_state = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!("exd5", game_state.move_to_san(Move::new(28, 35, None)));
+    }
+
+    #[test]
+    fn san_line() {
+        let moves = ["e2e4", "e7e5", "d1h5", "b8c6", "f1c4", "g8f6", "h5f7"]
+            .map(|uci| uci.parse::<Move>().unwrap());
+        assert_eq!(
+            Ok(["e4", "e5", "Qh5", "Nc6", "Bc4", "Nf6", "Qxf7#"]
+                .map(ToString::to_string)
+                .to_vec()),
+            GameState::default().san_line(&moves)
+        );
+        assert_eq!(
+            Err(MoveParseError::Illegal("e7e5".to_string())),
+            GameState::default().san_line(&moves[1..])
+        );
+        assert_eq!(Ok(vec![]), GameState::default().san_line(&[]));
+    }
+}
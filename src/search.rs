@@ -0,0 +1,458 @@
+use alloc::{vec, vec::Vec};
+use core::{
+    cmp::Reverse,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use crate::{
+    tt::{Bound, TranspositionTable},
+    DefaultEvaluator, Evaluator, GameState, Move,
+};
+
+pub const MATE_SCORE: i32 = 30_000;
+const INFINITY: i32 = 32_000;
+const MAX_PLY: usize = 64;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<Duration>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best_move: Option<Move>,
+    pub score: i32,
+    pub depth: u32,
+    pub pv: Vec<Move>,
+    pub nodes: u64,
+}
+
+struct Searcher<'a, E> {
+    evaluator: &'a E,
+    tt: &'a TranspositionTable,
+    limits: SearchLimits,
+    stop: &'a AtomicBool,
+    nodes: u64,
+    aborted: bool,
+    #[cfg(feature = "std")]
+    start: std::time::Instant,
+}
+
+impl<'a, E: Evaluator> Searcher<'a, E> {
+    fn new(
+        evaluator: &'a E,
+        tt: &'a TranspositionTable,
+        limits: SearchLimits,
+        stop: &'a AtomicBool,
+    ) -> Self {
+        Searcher {
+            evaluator,
+            tt,
+            limits,
+            stop,
+            nodes: 0,
+            aborted: false,
+            #[cfg(feature = "std")]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    fn should_stop(&mut self) -> bool {
+        if self.limits.nodes.is_some_and(|limit| self.nodes >= limit)
+            || self.stop.load(Ordering::Relaxed)
+        {
+            self.aborted = true;
+        }
+        #[cfg(feature = "std")]
+        if self.nodes.is_multiple_of(1024)
+            && self
+                .limits
+                .movetime
+                .is_some_and(|movetime| self.start.elapsed() >= movetime)
+        {
+            self.aborted = true;
+        }
+        self.aborted
+    }
+
+    fn ordered_moves(
+        &self,
+        position: &GameState,
+        first: Option<Move>,
+        tactical_only: bool,
+    ) -> Vec<Move> {
+        let color = position.active_color;
+        let mut scored: Vec<(i32, Move)> = position
+            .legal_moves_iter()
+            .filter_map(|mv| {
+                let tactical = mv.promotion().is_some()
+                    || position
+                        .get_piece_on(mv.to())
+                        .is_some_and(|target| target.color() != color);
+                let score = match () {
+                    _ if Some(mv) == first => INFINITY,
+                    _ if tactical => position.see(mv),
+                    _ if tactical_only => return None,
+                    _ => 0,
+                };
+                (!tactical_only || score >= 0).then_some((score, mv))
+            })
+            .collect();
+        scored.sort_by_key(|&(score, _)| Reverse(score));
+        scored.into_iter().map(|(_, mv)| mv).collect()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &mut self,
+        position: &mut GameState,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+        hint: &[Move],
+    ) -> i32 {
+        pv.clear();
+        if depth == 0 || ply >= MAX_PLY {
+            return self.quiescence(position, alpha, beta, ply);
+        }
+        self.nodes += 1;
+        if self.should_stop() {
+            return 0;
+        }
+        if ply > 0 && (position.is_fifty_move_draw() || position.is_insufficient_material()) {
+            return 0;
+        }
+        let key = position.zobrist_hash();
+        let entry = self.tt.probe(key);
+        if let Some(entry) = entry.filter(|entry| ply > 0 && entry.depth as u32 >= depth) {
+            let score = score_from_tt(entry.score, ply);
+            match entry.bound {
+                Bound::Lower | Bound::Exact if score >= beta => return beta,
+                Bound::Upper | Bound::Exact if score <= alpha => return alpha,
+                _ => {}
+            }
+        }
+        let first = hint
+            .first()
+            .copied()
+            .or(entry.and_then(|entry| entry.best_move));
+        let moves = self.ordered_moves(position, first, false);
+        if moves.is_empty() {
+            return if position.is_in_check(position.active_color) {
+                -MATE_SCORE + ply as i32
+            } else {
+                0
+            };
+        }
+        let original_alpha = alpha;
+        let mut best_move = None;
+        let mut child_pv = Vec::new();
+        for mv in moves {
+            let child_hint = match hint.split_first() {
+                Some((&first, rest)) if first == mv => rest,
+                _ => &[],
+            };
+            let undo = position.make_move(mv).expect("legal moves can be applied");
+            let score = -self.negamax(
+                position,
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+                &mut child_pv,
+                child_hint,
+            );
+            position.unmake_move(mv, undo);
+            if self.aborted {
+                return 0;
+            }
+            if score > alpha {
+                alpha = score;
+                best_move = Some(mv);
+                pv.clear();
+                pv.push(mv);
+                pv.extend_from_slice(&child_pv);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        let bound = if alpha >= beta {
+            Bound::Lower
+        } else if alpha > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        self.tt.store(
+            key,
+            best_move,
+            score_to_tt(alpha, ply),
+            depth.min(u8::MAX as u32) as u8,
+            bound,
+        );
+        alpha
+    }
+
+    fn quiescence(
+        &mut self,
+        position: &mut GameState,
+        mut alpha: i32,
+        beta: i32,
+        ply: usize,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.should_stop() {
+            return 0;
+        }
+        if position.is_in_check(position.active_color)
+            && position.legal_moves_iter().next().is_none()
+        {
+            return -MATE_SCORE + ply as i32;
+        }
+        let stand_pat = self.evaluator.evaluate(position);
+        if stand_pat >= beta {
+            return beta;
+        }
+        alpha = alpha.max(stand_pat);
+        if ply >= MAX_PLY {
+            return alpha;
+        }
+        for mv in self.ordered_moves(position, None, true) {
+            let undo = position.make_move(mv).expect("legal moves can be applied");
+            let score = -self.quiescence(position, -beta, -alpha, ply + 1);
+            position.unmake_move(mv, undo);
+            if self.aborted {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+}
+
+fn score_to_tt(score: i32, ply: usize) -> i32 {
+    match score {
+        _ if score >= MATE_SCORE - MAX_PLY as i32 => score + ply as i32,
+        _ if score <= -MATE_SCORE + MAX_PLY as i32 => score - ply as i32,
+        _ => score,
+    }
+}
+
+fn score_from_tt(score: i32, ply: usize) -> i32 {
+    match score {
+        _ if score >= MATE_SCORE - MAX_PLY as i32 => score - ply as i32,
+        _ if score <= -MATE_SCORE + MAX_PLY as i32 => score + ply as i32,
+        _ => score,
+    }
+}
+
+pub fn search(position: &GameState, limits: SearchLimits) -> SearchResult {
+    search_with(position, limits, &DefaultEvaluator)
+}
+
+pub fn search_with<E: Evaluator>(
+    position: &GameState,
+    limits: SearchLimits,
+    evaluator: &E,
+) -> SearchResult {
+    search_with_table(position, limits, evaluator, &TranspositionTable::new(1))
+}
+
+pub fn search_with_table<E: Evaluator>(
+    position: &GameState,
+    limits: SearchLimits,
+    evaluator: &E,
+    tt: &TranspositionTable,
+) -> SearchResult {
+    search_with_stop(position, limits, evaluator, tt, &AtomicBool::new(false))
+}
+
+pub fn search_with_stop<E: Evaluator>(
+    position: &GameState,
+    limits: SearchLimits,
+    evaluator: &E,
+    tt: &TranspositionTable,
+    stop: &AtomicBool,
+) -> SearchResult {
+    tt.new_search();
+    let mut searcher = Searcher::new(evaluator, tt, limits, stop);
+    let mut scratch = position.clone();
+    let mut result = SearchResult {
+        best_move: position.legal_moves_iter().next(),
+        score: 0,
+        depth: 0,
+        pv: vec![],
+        nodes: 0,
+    };
+    for depth in 1..=limits.depth.unwrap_or(MAX_PLY as u32) {
+        let mut pv = Vec::new();
+        let score = searcher.negamax(
+            &mut scratch,
+            depth,
+            0,
+            -INFINITY,
+            INFINITY,
+            &mut pv,
+            &result.pv,
+        );
+        if searcher.aborted {
+            break;
+        }
+        result = SearchResult {
+            best_move: pv.first().copied(),
+            score,
+            depth,
+            pv,
+            nodes: searcher.nodes,
+        };
+        if score.abs() >= MATE_SCORE - MAX_PLY as i32 {
+            break;
+        }
+    }
+    result.nodes = searcher.nodes;
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::AtomicBool;
+
+    use crate::{
+        search::{
+            search, search_with, search_with_stop, search_with_table, SearchLimits, MATE_SCORE,
+        },
+        tt::TranspositionTable,
+        Color::*,
+        DefaultEvaluator, GameState, Move,
+        Piece::*,
+    };
+
+    fn depth(depth: u32) -> SearchLimits {
+        SearchLimits {
+            depth: Some(depth),
+            ..SearchLimits::default()
+        }
+    }
+
+    #[test]
+    fn search_finds_mate_in_one() {
+        let game_state = "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let result = search(&game_state, depth(3));
+        assert_eq!(Some(Move::new(0, 56, None)), result.best_move);
+        assert_eq!(MATE_SCORE - 1, result.score);
+        assert_eq!(1, result.depth);
+    }
+
+    #[test]
+    fn search_finds_mate_in_two() {
+        let game_state = "6k1/5ppp/8/8/8/8/R7/1R4K1 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let result = search(&game_state, depth(4));
+        assert_eq!(MATE_SCORE - 1, result.score);
+        let game_state = "7k/8/8/8/8/8/R7/1R4K1 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let result = search(&game_state, depth(4));
+        assert_eq!(MATE_SCORE - 3, result.score);
+        assert_eq!(3, result.pv.len());
+        let mut position = game_state.clone();
+        for &mv in &result.pv {
+            position.make_move(mv).unwrap();
+        }
+        assert!(position.is_checkmate());
+    }
+
+    #[test]
+    fn quiescence_avoids_defended_pawn() {
+        let game_state = "4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let result = search(&game_state, depth(1));
+        assert_ne!(Some(Move::new(3, 35, None)), result.best_move);
+        let game_state = "4k3/8/8/3q4/8/8/8/3QK3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let result = search(&game_state, depth(2));
+        assert_eq!(Some(Move::new(3, 35, None)), result.best_move);
+    }
+
+    #[test]
+    fn search_limits() {
+        let result = search(
+            &GameState::default(),
+            SearchLimits {
+                nodes: Some(500),
+                ..SearchLimits::default()
+            },
+        );
+        assert!(result.best_move.is_some());
+        assert!(result.nodes <= 501);
+        let stalemate = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let result = search(&stalemate, depth(3));
+        assert_eq!(None, result.best_move);
+        assert_eq!(0, result.score);
+    }
+
+    #[test]
+    fn search_with_custom_evaluator() {
+        // Rewards the side to move for having its king far up the board.
+        let king_rank = |state: &GameState| {
+            let rank = |color| state.get_bitboard(King(color)).first().unwrap_or(0) as i32 / 8;
+            let score = rank(White) - (7 - rank(Black));
+            match state.active_color {
+                White => score,
+                Black => -score,
+            }
+        };
+        let game_state = "4k3/8/8/8/8/8/8/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let result = search_with(&game_state, depth(1), &king_rank);
+        assert_eq!(1, result.score);
+        assert_eq!(1, result.best_move.unwrap().to() / 8);
+    }
+
+    #[test]
+    fn search_with_shared_table() {
+        let game_state = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let without_table = search(&game_state, depth(3));
+        let tt = TranspositionTable::new(4);
+        let first = search_with_table(&game_state, depth(3), &DefaultEvaluator, &tt);
+        assert!(!tt.is_empty());
+        assert_eq!(without_table.score, first.score);
+        let second = search_with_table(&game_state, depth(3), &DefaultEvaluator, &tt);
+        assert_eq!(first.score, second.score);
+        assert!(second.nodes < first.nodes);
+    }
+
+    #[test]
+    fn search_honours_stop_flag() {
+        let stop = AtomicBool::new(true);
+        let result = search_with_stop(
+            &GameState::default(),
+            SearchLimits::default(),
+            &DefaultEvaluator,
+            &TranspositionTable::new(1),
+            &stop,
+        );
+        assert_eq!(0, result.depth);
+        assert_eq!(1, result.nodes);
+        assert!(result.best_move.is_some());
+    }
+}
@@ -0,0 +1,298 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use serde::{
+    de::{Error, MapAccess, SeqAccess, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{Bitboard, Color, Game, GameState, Move, Piece};
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            Color::White => "w",
+            Color::Black => "b",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "w" => Ok(Color::White),
+            "b" => Ok(Color::Black),
+            other => Err(D::Error::custom(format!("invalid color {other}"))),
+        }
+    }
+}
+
+impl Serialize for Bitboard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bitboard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u64::deserialize(deserializer).map(Bitboard)
+    }
+}
+
+impl Serialize for Piece {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.fen())
+    }
+}
+
+impl<'de> Deserialize<'de> for Piece {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        let mut chars = string.chars();
+        match (chars.next().and_then(Piece::from_char), chars.next()) {
+            (Some(piece), None) => Ok(piece),
+            _ => Err(D::Error::custom(format!("invalid piece {string}"))),
+        }
+    }
+}
+
+impl Serialize for GameState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for GameState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for Move {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Move {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for Game {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut game = serializer.serialize_struct("Game", 2)?;
+        game.serialize_field("start", self.start())?;
+        game.serialize_field("moves", self.moves())?;
+        game.end()
+    }
+}
+
+fn replay<E: Error>(start: GameState, moves: Vec<Move>) -> Result<Game, E> {
+    let mut game = Game::new(start);
+    for (index, mv) in moves.into_iter().enumerate() {
+        if !game.position().legal_moves_iter().any(|legal| legal == mv) {
+            return Err(E::custom(format!("move {index} ({mv}) is illegal")));
+        }
+        game.make_move(mv)
+            .map_err(|err| E::custom(format!("move {index} ({mv}): {err}")))?;
+    }
+    Ok(game)
+}
+
+struct GameVisitor;
+
+impl<'de> Visitor<'de> for GameVisitor {
+    type Value = Game;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "a game with a start position and moves")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Game, A::Error> {
+        let start = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let moves = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        replay(start, moves)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Game, A::Error> {
+        let mut start = None;
+        let mut moves = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "start" => start = Some(map.next_value()?),
+                "moves" => moves = Some(map.next_value()?),
+                other => return Err(A::Error::unknown_field(other, &["start", "moves"])),
+            }
+        }
+        replay(
+            start.ok_or_else(|| A::Error::missing_field("start"))?,
+            moves.unwrap_or_default(),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for Game {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Game", &["start", "moves"], GameVisitor)
+    }
+}
+
+pub mod bitboards {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::{occupancy_of, Bitboard, Color, GameState};
+
+    type Compact = (
+        [Bitboard; 12],
+        Color,
+        [bool; 4],
+        [u8; 4],
+        Option<u8>,
+        u16,
+        u16,
+    );
+
+    pub fn serialize<S: Serializer>(state: &GameState, serializer: S) -> Result<S::Ok, S::Error> {
+        (
+            state.bitboards,
+            state.active_color,
+            state.castling_rights,
+            state.castling_rook_files,
+            state.en_passant_target,
+            state.half_move_clock,
+            state.full_move_number,
+        )
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GameState, D::Error> {
+        let (
+            bitboards,
+            active_color,
+            castling_rights,
+            castling_rook_files,
+            en_passant_target,
+            half_move_clock,
+            full_move_number,
+        ) = Compact::deserialize(deserializer)?;
+        let total: u32 = bitboards.iter().map(|bitboard| bitboard.count()).sum();
+        if bitboards
+            .iter()
+            .fold(Bitboard::EMPTY, |all, bitboard| all | *bitboard)
+            .count()
+            != total
+        {
+            return Err(D::Error::custom("overlapping piece bitboards"));
+        }
+        if castling_rook_files.iter().any(|&file| file >= 8)
+            || en_passant_target.is_some_and(|square| square >= 64)
+        {
+            return Err(D::Error::custom("square out of range"));
+        }
+        let state = GameState {
+            occupancy: occupancy_of(&bitboards),
+            bitboards,
+            active_color,
+            castling_rights,
+            castling_rook_files,
+            en_passant_target,
+            half_move_clock,
+            full_move_number,
+        };
+        state.validate().map_err(D::Error::custom)?;
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use crate::{serde_bitboards, Color::*, Game, GameState, Move, Piece::*};
+
+    #[test]
+    fn default_position_round_trips_through_json() {
+        let game_state = GameState::default();
+        let json = serde_json::to_string(&game_state).unwrap();
+        assert_eq!(
+            "\"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1\"",
+            json
+        );
+        assert_eq!(game_state, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn pieces_and_colors_serialize_as_fen_letters() {
+        assert_eq!("\"n\"", serde_json::to_string(&Knight(Black)).unwrap());
+        assert_eq!(Queen(White), serde_json::from_str("\"Q\"").unwrap());
+        assert_eq!("\"w\"", serde_json::to_string(&White).unwrap());
+        assert_eq!(Black, serde_json::from_str("\"b\"").unwrap());
+        assert!(serde_json::from_str::<GameState>("\"not a fen\"").is_err());
+    }
+
+    #[test]
+    fn moves_serialize_as_uci() {
+        let mv = Move::new(52, 60, Some(Queen(White)));
+        assert_eq!("\"e7e8q\"", serde_json::to_string(&mv).unwrap());
+        assert_eq!(mv, serde_json::from_str("\"e7e8q\"").unwrap());
+        assert!(serde_json::from_str::<Move>("\"e7\"").is_err());
+    }
+
+    #[test]
+    fn games_round_trip_through_json() {
+        let mut game = Game::default();
+        for uci in ["e2e4", "e7e5", "g1f3"] {
+            game.make_move(uci.parse().unwrap()).unwrap();
+        }
+        let json = serde_json::to_string(&game).unwrap();
+        assert_eq!(
+            "{\"start\":\"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1\",\"moves\":[\"e2e4\",\"e7e5\",\"g1f3\"]}",
+            json
+        );
+        let restored: Game = serde_json::from_str(&json).unwrap();
+        assert_eq!(game.position(), restored.position());
+        assert_eq!(game.moves(), restored.moves());
+        let start = GameState::default().to_string();
+        let illegal = format!("{{\"start\":\"{start}\",\"moves\":[\"e2e5\"]}}");
+        assert!(serde_json::from_str::<Game>(&illegal).is_err());
+    }
+
+    #[test]
+    fn compact_bitboard_representation() {
+        let position = GameState::default().play_moves(&["e4", "c5"]).unwrap();
+        let mut json = Vec::new();
+        serde_bitboards::serialize(&position, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(
+            "[[268496640,70650236334243840,66,4755801206503243776,36,2594073385365405696,129,9295429630892703744,8,576460752303423488,16,1152921504606846976],\"w\",[true,true,true,true],[7,0,7,0],42,0,2]",
+            json
+        );
+        let decode = |json: &str| {
+            serde_bitboards::deserialize(&mut serde_json::Deserializer::from_str(json))
+        };
+        assert_eq!(position, decode(&json).unwrap());
+        assert!(decode(&json.replacen("268496640", "268496641", 1)).is_err());
+        assert!(decode(&json.replacen(",42,", ",64,", 1)).is_err());
+        assert!(decode(
+            "[[0,0,0,0,0,0,0,0,0,0,0,0],\"w\",[true,true,true,true],[7,0,7,0],null,0,1]"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use alloc::{format, string::String};
+use core::{fmt::Display, str::FromStr};
+
+use crate::{Bitboard, FILES, RANKS, RANK_MATRIX};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum File {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+}
+
+impl File {
+    pub const ALL: [File; 8] = [
+        File::A,
+        File::B,
+        File::C,
+        File::D,
+        File::E,
+        File::F,
+        File::G,
+        File::H,
+    ];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        File::ALL.get(index).copied()
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn bitboard(&self) -> Bitboard {
+        FILES[self.index()]
+    }
+}
+
+impl Display for File {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", RANK_MATRIX[self.index()])
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rank {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+    Seventh,
+    Eighth,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 8] = [
+        Rank::First,
+        Rank::Second,
+        Rank::Third,
+        Rank::Fourth,
+        Rank::Fifth,
+        Rank::Sixth,
+        Rank::Seventh,
+        Rank::Eighth,
+    ];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Rank::ALL.get(index).copied()
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn bitboard(&self) -> Bitboard {
+        RANKS[self.index()]
+    }
+}
+
+impl Display for Rank {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.index() + 1)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Square(u8);
+
+impl Square {
+    pub fn new(file: File, rank: Rank) -> Self {
+        Square((rank.index() * 8 + file.index()) as u8)
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        (index < 64).then_some(Square(index as u8))
+    }
+
+    pub fn from_algebraic(str: &str) -> Option<Self> {
+        let mut chars = str.chars();
+        let file = chars.next()?;
+        let rank = chars.next()?;
+        if chars.next().is_some() || !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+            return None;
+        }
+        Some(Square::new(
+            File::from_index(file as usize - 'a' as usize)?,
+            Rank::from_index(rank as usize - '1' as usize)?,
+        ))
+    }
+
+    pub fn file(&self) -> File {
+        File::ALL[self.index() % 8]
+    }
+
+    pub fn rank(&self) -> Rank {
+        Rank::ALL[self.index() / 8]
+    }
+
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn bitboard(&self) -> Bitboard {
+        Bitboard::from_square(self.index())
+    }
+
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..64).map(Square)
+    }
+}
+
+impl FromStr for Square {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        Square::from_algebraic(str).ok_or(format!("Invalid square {str}"))
+    }
+}
+
+impl Display for Square {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}", self.file(), self.rank())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use crate::{Bitboard, Color::*, File, GameState, Piece::*, Rank, Square};
+
+    #[test]
+    fn algebraic_round_trip() {
+        for index in 0..64 {
+            let square = Square::from_index(index).unwrap();
+            assert_eq!(index, square.index());
+            let name = square.to_string();
+            assert_eq!(Some(square), Square::from_algebraic(&name));
+            assert_eq!(square, Square::new(square.file(), square.rank()));
+        }
+        assert_eq!(None, Square::from_index(64));
+    }
+
+    #[test]
+    fn coordinates() {
+        let e4 = Square::from_algebraic("e4").unwrap();
+        assert_eq!(28, e4.index());
+        assert_eq!(File::E, e4.file());
+        assert_eq!(Rank::Fourth, e4.rank());
+        assert_eq!("a1", Square::new(File::A, Rank::First).to_string());
+        assert_eq!("h8", Square::new(File::H, Rank::Eighth).to_string());
+        for bad in ["", "e", "e0", "e9", "i4", "E4", "e44"] {
+            assert_eq!(None, Square::from_algebraic(bad), "{bad}");
+        }
+    }
+
+    #[test]
+    fn file_and_rank_bitboards() {
+        assert_eq!(0x0101010101010101, File::A.bitboard());
+        assert_eq!(0x8080808080808080, File::H.bitboard());
+        assert_eq!(0x00000000000000FF, Rank::First.bitboard());
+        assert_eq!(0xFF00000000000000, Rank::Eighth.bitboard());
+        assert_eq!(
+            File::ALL
+                .iter()
+                .fold(Bitboard::EMPTY, |all, file| all | file.bitboard()),
+            u64::MAX
+        );
+        assert_eq!(Some(File::C), File::from_index(2));
+        assert_eq!(None, Rank::from_index(8));
+        assert_eq!("c", File::C.to_string());
+        assert_eq!("7", Rank::Seventh.to_string());
+    }
+
+    #[test]
+    fn fen_rank_mapping() {
+        let game_state = GameState::default();
+        let white_pawns = *game_state.get_bitboard(Pawn(White));
+        let black_rooks = *game_state.get_bitboard(Rook(Black));
+        assert_eq!(Rank::Second.bitboard(), white_pawns);
+        assert_eq!(
+            (File::A.bitboard() | File::H.bitboard()) & Rank::Eighth.bitboard(),
+            black_rooks
+        );
+    }
+
+    #[test]
+    fn square_conversions() {
+        assert_eq!(
+            Ok(Square::new(File::E, Rank::Fourth)),
+            "e4".parse::<Square>()
+        );
+        assert!("j1".parse::<Square>().is_err());
+        assert_eq!(1 << 28, "e4".parse::<Square>().unwrap().bitboard());
+        assert_eq!(64, Square::all().count());
+        assert_eq!(
+            u64::MAX,
+            Square::all().fold(Bitboard::EMPTY, |all, square| all | square.bitboard())
+        );
+        let game_state = "4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 2"
+            .parse::<GameState>()
+            .unwrap();
+        let e6 = game_state.get_en_passant_square().unwrap();
+        assert_eq!("e6", e6.to_string());
+        assert_eq!(
+            Some(Pawn(Black)),
+            game_state.get_piece_on_square("e5".parse().unwrap())
+        );
+    }
+}
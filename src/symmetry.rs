@@ -0,0 +1,114 @@
+use crate::{Bitboard, GameState};
+
+impl GameState {
+    pub fn flipped(&self) -> GameState {
+        let mut bitboards = [Bitboard::EMPTY; 12];
+        for (index, bitboard) in self.bitboards.iter().enumerate() {
+            bitboards[index ^ 1] = bitboard.flip_vertical();
+        }
+        let [white_king, white_queen, black_king, black_queen] = self.castling_rights;
+        let [white_king_file, white_queen_file, black_king_file, black_queen_file] =
+            self.castling_rook_files;
+        GameState {
+            bitboards,
+            occupancy: [
+                self.occupancy[1].flip_vertical(),
+                self.occupancy[0].flip_vertical(),
+            ],
+            active_color: self.active_color.opposite(),
+            castling_rights: [black_king, black_queen, white_king, white_queen],
+            castling_rook_files: [
+                black_king_file,
+                black_queen_file,
+                white_king_file,
+                white_queen_file,
+            ],
+            en_passant_target: self.en_passant_target.map(|target| target ^ 56),
+            half_move_clock: self.half_move_clock,
+            full_move_number: self.full_move_number,
+        }
+    }
+
+    pub fn mirror_files(&self) -> GameState {
+        let [white_king, white_queen, black_king, black_queen] = self.castling_rights;
+        let [white_king_file, white_queen_file, black_king_file, black_queen_file] =
+            self.castling_rook_files;
+        GameState {
+            bitboards: self.bitboards.map(Bitboard::flip_horizontal),
+            occupancy: self.occupancy.map(Bitboard::flip_horizontal),
+            active_color: self.active_color,
+            castling_rights: [white_queen, white_king, black_queen, black_king],
+            castling_rook_files: [
+                7 - white_queen_file,
+                7 - white_king_file,
+                7 - black_queen_file,
+                7 - black_king_file,
+            ],
+            en_passant_target: self.en_passant_target.map(|target| target ^ 7),
+            half_move_clock: self.half_move_clock,
+            full_move_number: self.full_move_number,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use crate::{Color::*, GameState, Piece::*};
+
+    #[test]
+    fn flipped_twice_is_identity() {
+        let game_state = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w Kq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(game_state, game_state.flipped().flipped());
+    }
+
+    #[test]
+    fn flipped_positions() {
+        let game_state = GameState::default();
+        let mut flipped = game_state.flipped();
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1",
+            flipped.to_string()
+        );
+        flipped.active_color = game_state.active_color;
+        assert_eq!(game_state, flipped);
+
+        let game_state = "rnbqkbnr/pppp1ppp/8/8/3pP3/8/PPP2PPP/RNBQKBNR b Kq e3 0 3"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            "rnbqkbnr/ppp2ppp/8/3Pp3/8/8/PPPP1PPP/RNBQKBNR w Qk e6 0 3",
+            game_state.flipped().to_string()
+        );
+        assert_eq!(game_state.perft(2), game_state.flipped().perft(2));
+    }
+
+    #[test]
+    fn mirror_files() {
+        let game_state = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w Kq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(game_state, game_state.mirror_files().mirror_files());
+
+        let game_state = "4k3/8/8/8/8/8/8/R3K3 w Q - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let mirrored = game_state.mirror_files();
+        assert_eq!(1 << 7, *mirrored.get_bitboard(Rook(White)));
+        assert_eq!(1 << 3, *mirrored.get_bitboard(King(White)));
+        assert_eq!(1 << 59, *mirrored.get_bitboard(King(Black)));
+        assert_eq!("3k4/8/8/8/8/8/8/3K3R w H - 0 1", mirrored.to_string());
+        assert_eq!(game_state.material_balance(), mirrored.material_balance());
+
+        let game_state = "4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 2"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            "3k4/8/8/3pP3/8/8/8/3K4 w - d6 0 2",
+            game_state.mirror_files().to_string()
+        );
+    }
+}
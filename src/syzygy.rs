@@ -0,0 +1,1285 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{fmt::Display, ops::Neg};
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::{Bitboard, Color, Color::*, GameState, Move, Piece::*};
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+const MAX_PIECES: usize = 7;
+
+const SPLIT: u8 = 1;
+const HAS_PAWNS: u8 = 2;
+
+const STM: u8 = 1;
+const MAPPED: u8 = 2;
+const WIN_PLIES: u8 = 4;
+const LOSS_PLIES: u8 = 8;
+const WIDE: u8 = 16;
+const SINGLE_VALUE: u8 = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Wdl {
+    Loss = -2,
+    BlessedLoss = -1,
+    Draw = 0,
+    CursedWin = 1,
+    Win = 2,
+}
+
+impl Wdl {
+    fn from_value(value: i32) -> Wdl {
+        match value {
+            ..=-2 => Wdl::Loss,
+            -1 => Wdl::BlessedLoss,
+            0 => Wdl::Draw,
+            1 => Wdl::CursedWin,
+            _ => Wdl::Win,
+        }
+    }
+
+    fn signum(self) -> i32 {
+        (self as i32).signum()
+    }
+}
+
+impl Neg for Wdl {
+    type Output = Wdl;
+
+    fn neg(self) -> Wdl {
+        Wdl::from_value(-(self as i32))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SyzygyError {
+    MissingTable(String),
+    Castling,
+    Corrupt(String),
+    Io(String),
+}
+
+impl Display for SyzygyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SyzygyError::MissingTable(name) => write!(f, "no tablebase for {name}"),
+            SyzygyError::Castling => write!(f, "tablebases do not cover castling rights"),
+            SyzygyError::Corrupt(name) => write!(f, "tablebase {name} is corrupt"),
+            SyzygyError::Io(err) => write!(f, "could not read tablebase: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SyzygyError {}
+
+fn off_diagonal(square: usize) -> i32 {
+    (square / 8) as i32 - (square % 8) as i32
+}
+
+fn flip_diagonal(square: usize) -> usize {
+    ((square >> 3) | (square << 3)) & 63
+}
+
+// The index tables shared by every table, laid out exactly as the generator
+// expects them.
+struct Indices {
+    map_pawns: [usize; 64],
+    map_b1h1h7: [usize; 64],
+    map_a1d1d4: [usize; 64],
+    map_kk: [[usize; 64]; 10],
+    binomial: [[u64; 64]; 6],
+    lead_pawn_idx: [[u64; 64]; 6],
+    lead_pawns_size: [[u64; 4]; 6],
+}
+
+fn indices() -> &'static Indices {
+    static INDICES: OnceLock<Indices> = OnceLock::new();
+    INDICES.get_or_init(|| {
+        let mut indices = Indices {
+            map_pawns: [0; 64],
+            map_b1h1h7: [0; 64],
+            map_a1d1d4: [0; 64],
+            map_kk: [[0; 64]; 10],
+            binomial: [[0; 64]; 6],
+            lead_pawn_idx: [[0; 64]; 6],
+            lead_pawns_size: [[0; 4]; 6],
+        };
+        for (code, square) in (0..64)
+            .filter(|&square| off_diagonal(square) < 0)
+            .enumerate()
+        {
+            indices.map_b1h1h7[square] = code;
+        }
+        // a1-d4 is squares 0..=27; the triangle below the diagonal comes
+        // first and the diagonal squares are numbered last.
+        let triangle = (0..28).filter(|&square| square % 8 <= 3 && off_diagonal(square) < 0);
+        let diagonal = (0..28).filter(|&square| square % 8 <= 3 && off_diagonal(square) == 0);
+        for (code, square) in triangle.chain(diagonal).enumerate() {
+            indices.map_a1d1d4[square] = code;
+        }
+        let mut code = 0;
+        let mut both_on_diagonal = Vec::new();
+        for idx in 0..10 {
+            for first in 0..28 {
+                // b1 is code 0, but so is every square outside the triangle.
+                if indices.map_a1d1d4[first] != idx || (idx == 0 && first != 1) {
+                    continue;
+                }
+                for second in 0..64 {
+                    let distance = ((first % 8) as i32 - (second % 8) as i32)
+                        .abs()
+                        .max(((first / 8) as i32 - (second / 8) as i32).abs());
+                    if distance <= 1 || (off_diagonal(first) == 0 && off_diagonal(second) > 0) {
+                        continue;
+                    }
+                    if off_diagonal(first) == 0 && off_diagonal(second) == 0 {
+                        both_on_diagonal.push((idx, second));
+                    } else {
+                        indices.map_kk[idx][second] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+        for (idx, second) in both_on_diagonal {
+            indices.map_kk[idx][second] = code;
+            code += 1;
+        }
+        indices.binomial[0][0] = 1;
+        for n in 1..64 {
+            for k in 0..6.min(n + 1) {
+                indices.binomial[k][n] = if k > 0 {
+                    indices.binomial[k - 1][n - 1]
+                } else {
+                    0
+                } + if k < n { indices.binomial[k][n - 1] } else { 0 };
+            }
+        }
+        let mut available = 47;
+        for lead_pawns in 1..6 {
+            for file in 0..4 {
+                let mut idx = 0;
+                for rank in 1..7 {
+                    let square = rank * 8 + file;
+                    if lead_pawns == 1 {
+                        indices.map_pawns[square] = available;
+                        indices.map_pawns[square ^ 7] = available - 1;
+                        available = available.saturating_sub(2);
+                    }
+                    indices.lead_pawn_idx[lead_pawns][square] = idx;
+                    idx += indices.binomial[lead_pawns - 1][indices.map_pawns[square]];
+                }
+                indices.lead_pawns_size[lead_pawns][file] = idx;
+            }
+        }
+        indices
+    })
+}
+
+#[derive(Clone, Debug)]
+struct Material {
+    name: String,
+    symmetric: bool,
+    has_pawns: bool,
+    unique_pieces: bool,
+    pawn_count: [usize; 2],
+    piece_count: usize,
+}
+
+const PIECE_LETTERS: [char; 6] = ['K', 'Q', 'R', 'B', 'N', 'P'];
+
+impl Material {
+    fn parse(name: &str) -> Option<Material> {
+        let (white, black) = name.split_once('v')?;
+        let side = |side: &str| {
+            let mut counts = [0; 6];
+            for letter in side.chars() {
+                counts[PIECE_LETTERS.iter().position(|&piece| piece == letter)?] += 1;
+            }
+            (counts[0] == 1 && side.starts_with('K')).then_some(counts)
+        };
+        let (white, black) = (side(white)?, side(black)?);
+        if material_name(white, black) != name
+            || white.iter().chain(&black).sum::<usize>() > MAX_PIECES
+        {
+            return None;
+        }
+        let leading_white = black[5] == 0 || (white[5] > 0 && black[5] >= white[5]);
+        Some(Material {
+            name: name.to_string(),
+            symmetric: white == black,
+            has_pawns: white[5] + black[5] > 0,
+            unique_pieces: white[1..]
+                .iter()
+                .chain(&black[1..])
+                .any(|&count| count == 1),
+            pawn_count: if leading_white {
+                [white[5], black[5]]
+            } else {
+                [black[5], white[5]]
+            },
+            piece_count: white.iter().chain(&black).sum(),
+        })
+    }
+}
+
+fn material_name(white: [usize; 6], black: [usize; 6]) -> String {
+    let side = |counts: [usize; 6]| {
+        PIECE_LETTERS
+            .iter()
+            .zip(counts)
+            .flat_map(|(&letter, count)| core::iter::repeat_n(letter, count))
+            .collect::<String>()
+    };
+    format!("{}v{}", side(white), side(black))
+}
+
+fn piece_counts(state: &GameState, color: Color) -> [usize; 6] {
+    [
+        King(color),
+        Queen(color),
+        Rook(color),
+        Bishop(color),
+        Knight(color),
+        Pawn(color),
+    ]
+    .map(|piece| state.count(piece) as usize)
+}
+
+// Generator piece codes: 1-6 for white pawn to king, plus 8 for black.
+fn piece_code(index: usize) -> u8 {
+    (index / 2 + 1) as u8 | (index % 2 * 8) as u8
+}
+
+fn byte(bytes: &[u8], at: usize) -> Option<u8> {
+    bytes.get(at).copied()
+}
+
+fn le_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes([byte(bytes, at)?, byte(bytes, at + 1)?]))
+}
+
+fn le_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+// The Huffman bit stream may be read a few bytes past the end of the file.
+fn be_u32(bytes: &[u8], at: usize) -> u32 {
+    let mut word = [0; 4];
+    for (offset, byte) in word.iter_mut().enumerate() {
+        *byte = bytes.get(at + offset).copied().unwrap_or(0);
+    }
+    u32::from_be_bytes(word)
+}
+
+#[derive(Clone, Debug, Default)]
+struct PairsData {
+    flags: u8,
+    pieces: [u8; MAX_PIECES],
+    group_len: [usize; MAX_PIECES + 1],
+    group_idx: [u64; MAX_PIECES + 1],
+    // Holds the value itself for single-value tables.
+    min_sym_len: u8,
+    lowest_sym: usize,
+    base64: Vec<u64>,
+    symlen: Vec<u8>,
+    btree: usize,
+    sizeof_block: u64,
+    span: u64,
+    sparse_index_size: usize,
+    sparse_index: usize,
+    num_blocks: usize,
+    block_length_size: usize,
+    block_length: usize,
+    data: usize,
+    map_idx: [usize; 4],
+}
+
+impl PairsData {
+    fn set_groups(&mut self, material: &Material, order: [u8; 2], file: usize) {
+        let indices = indices();
+        let mut n = 0;
+        let mut first_len: i32 = match (material.has_pawns, material.unique_pieces) {
+            (true, _) => 0,
+            (false, true) => 3,
+            (false, false) => 2,
+        };
+        self.group_len[0] = 1;
+        for i in 1..material.piece_count {
+            first_len -= 1;
+            if first_len > 0 || self.pieces[i] == self.pieces[i - 1] {
+                self.group_len[n] += 1;
+            } else {
+                n += 1;
+                self.group_len[n] = 1;
+            }
+        }
+        n += 1;
+        self.group_len[n] = 0;
+        let both_pawns = material.has_pawns && material.pawn_count[1] > 0;
+        let mut next = if both_pawns { 2 } else { 1 };
+        let mut free_squares =
+            64 - self.group_len[0] - if both_pawns { self.group_len[1] } else { 0 };
+        let mut idx = 1;
+        let mut k = 0;
+        while next < n || k == order[0] as usize || k == order[1] as usize {
+            if k == order[0] as usize {
+                self.group_idx[0] = idx;
+                idx *= if material.has_pawns {
+                    indices.lead_pawns_size[self.group_len[0]][file]
+                } else if material.unique_pieces {
+                    31332
+                } else {
+                    462
+                };
+            } else if k == order[1] as usize {
+                self.group_idx[1] = idx;
+                idx *= indices.binomial[self.group_len[1]][48 - self.group_len[0]];
+            } else {
+                self.group_idx[next] = idx;
+                idx *= indices.binomial[self.group_len[next]][free_squares];
+                free_squares -= self.group_len[next];
+                next += 1;
+            }
+            k += 1;
+        }
+        self.group_idx[n] = idx;
+    }
+
+    fn set_sizes(&mut self, bytes: &[u8], mut at: usize) -> Option<usize> {
+        self.flags = byte(bytes, at)?;
+        at += 1;
+        if self.flags & SINGLE_VALUE != 0 {
+            self.min_sym_len = byte(bytes, at)?;
+            return Some(at + 1);
+        }
+        let groups = self.group_len.iter().position(|&len| len == 0)?;
+        let tb_size = self.group_idx[groups];
+        self.sizeof_block = 1_u64.checked_shl(byte(bytes, at)? as u32)?;
+        self.span = 1_u64.checked_shl(byte(bytes, at + 1)? as u32)?;
+        self.sparse_index_size = tb_size.div_ceil(self.span) as usize;
+        let padding = byte(bytes, at + 2)? as usize;
+        self.num_blocks = le_u32(bytes, at + 3)? as usize;
+        self.block_length_size = self.num_blocks + padding;
+        let max_sym_len = byte(bytes, at + 7)?;
+        self.min_sym_len = byte(bytes, at + 8)?;
+        at += 9;
+        self.lowest_sym = at;
+        let lengths = max_sym_len.checked_sub(self.min_sym_len)? as usize + 1;
+        let lowest = |i: usize| le_u16(bytes, self.lowest_sym + 2 * i).map(u64::from);
+        self.base64 = vec![0; lengths];
+        for i in (0..lengths - 1).rev() {
+            self.base64[i] = (self.base64[i + 1]
+                .wrapping_add(lowest(i)?)
+                .wrapping_sub(lowest(i + 1)?))
+                / 2;
+        }
+        for (i, base) in self.base64.iter_mut().enumerate() {
+            let shift = 64 - i as u32 - self.min_sym_len as u32;
+            *base = base.checked_shl(shift).unwrap_or(0);
+        }
+        at += 2 * lengths;
+        let symbols = le_u16(bytes, at)? as usize;
+        at += 2;
+        self.btree = at;
+        bytes.get(at..at + 3 * symbols)?;
+        self.symlen = vec![0; symbols];
+        let mut visited = vec![false; symbols];
+        for symbol in 0..symbols {
+            if !visited[symbol] {
+                self.symlen[symbol] = self.set_symlen(bytes, symbol, &mut visited)?;
+            }
+        }
+        Some(at + 3 * symbols + (symbols & 1))
+    }
+
+    fn set_symlen(&mut self, bytes: &[u8], symbol: usize, visited: &mut [bool]) -> Option<u8> {
+        visited[symbol] = true;
+        let (left, right) = self.children(bytes, symbol);
+        if right == 0xFFF {
+            return Some(0);
+        }
+        for child in [left, right] {
+            if !*visited.get(child)? {
+                self.symlen[child] = self.set_symlen(bytes, child, visited)?;
+            }
+        }
+        self.symlen[left]
+            .checked_add(self.symlen[right])?
+            .checked_add(1)
+    }
+
+    // Each node packs two 12-bit symbols; a leaf stores its value on the left.
+    fn children(&self, bytes: &[u8], symbol: usize) -> (usize, usize) {
+        let at = self.btree + 3 * symbol;
+        let lr = [bytes[at], bytes[at + 1], bytes[at + 2]];
+        (
+            ((lr[1] as usize & 0xF) << 8) | lr[0] as usize,
+            ((lr[2] as usize) << 4) | (lr[1] as usize >> 4),
+        )
+    }
+
+    fn decompress(&self, bytes: &[u8], idx: u64) -> Option<i32> {
+        if self.flags & SINGLE_VALUE != 0 {
+            return Some(self.min_sym_len as i32);
+        }
+        // Sparse entry k points at the value with index k * span + span / 2.
+        let k = (idx / self.span) as usize;
+        let entry = self.sparse_index + 6 * k;
+        let mut block = le_u32(bytes, entry)? as usize;
+        let mut offset = le_u16(bytes, entry + 4)? as i64;
+        offset += (idx % self.span) as i64 - (self.span / 2) as i64;
+        let block_length =
+            |block: usize| le_u16(bytes, self.block_length + 2 * block).map(|length| length as i64);
+        while offset < 0 {
+            block = block.checked_sub(1)?;
+            offset += block_length(block)? + 1;
+        }
+        while offset > block_length(block)? {
+            offset -= block_length(block)? + 1;
+            block += 1;
+        }
+        let mut at = self.data + block * self.sizeof_block as usize;
+        let mut buffer = (be_u32(bytes, at) as u64) << 32 | be_u32(bytes, at + 4) as u64;
+        at += 8;
+        let mut buffer_size = 64;
+        let min_sym_len = self.min_sym_len as usize;
+        let mut symbol = loop {
+            let mut len = 0;
+            while buffer < *self.base64.get(len)? {
+                len += 1;
+            }
+            let shift = 64 - len - min_sym_len;
+            let symbol = ((buffer - self.base64[len])
+                .checked_shr(shift as u32)
+                .unwrap_or(0) as usize)
+                + le_u16(bytes, self.lowest_sym + 2 * len)? as usize;
+            let width = *self.symlen.get(symbol)? as i64 + 1;
+            if offset < width {
+                break symbol;
+            }
+            offset -= width;
+            len += min_sym_len;
+            buffer = buffer.checked_shl(len as u32).unwrap_or(0);
+            buffer_size -= len as i32;
+            if buffer_size <= 32 {
+                buffer_size += 32;
+                buffer |= (be_u32(bytes, at) as u64)
+                    .checked_shl((64 - buffer_size) as u32)
+                    .unwrap_or(0);
+                at += 4;
+            }
+        };
+        while self.symlen[symbol] != 0 {
+            let (left, right) = self.children(bytes, symbol);
+            let width = *self.symlen.get(left)? as i64 + 1;
+            if offset < width {
+                symbol = left;
+            } else {
+                offset -= width;
+                symbol = right;
+            }
+        }
+        Some(self.children(bytes, symbol).0 as i32)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Wdl,
+    Dtz,
+}
+
+struct TableFile {
+    bytes: Vec<u8>,
+    // Indexed by side to move (WDL only) and then by leading pawn file.
+    pairs: Vec<[PairsData; 4]>,
+}
+
+impl TableFile {
+    fn parse(bytes: Vec<u8>, material: &Material, kind: Kind) -> Option<TableFile> {
+        let magic = match kind {
+            Kind::Wdl => WDL_MAGIC,
+            Kind::Dtz => DTZ_MAGIC,
+        };
+        if bytes.get(..4)? != magic {
+            return None;
+        }
+        let flags = byte(&bytes, 4)?;
+        if (flags & HAS_PAWNS != 0) != material.has_pawns
+            || (flags & SPLIT != 0) == material.symmetric
+        {
+            return None;
+        }
+        let sides = if kind == Kind::Wdl && !material.symmetric {
+            2
+        } else {
+            1
+        };
+        let files = if material.has_pawns { 4 } else { 1 };
+        let both_pawns = material.has_pawns && material.pawn_count[1] > 0;
+        let mut pairs = vec![<[PairsData; 4]>::default(); sides];
+        let mut at = 5;
+        for file in 0..files {
+            let first = byte(&bytes, at)?;
+            let second = if both_pawns {
+                byte(&bytes, at + 1)?
+            } else {
+                0xFF
+            };
+            let order = [[first & 0xF, second & 0xF], [first >> 4, second >> 4]];
+            at += 1 + both_pawns as usize;
+            for k in 0..material.piece_count {
+                let pieces = byte(&bytes, at)?;
+                for (side, pairs) in pairs.iter_mut().enumerate() {
+                    pairs[file].pieces[k] = if side == 1 { pieces >> 4 } else { pieces & 0xF };
+                }
+                at += 1;
+            }
+            for (side, pairs) in pairs.iter_mut().enumerate() {
+                pairs[file].set_groups(material, order[side], file);
+            }
+        }
+        at += at & 1;
+        for file in 0..files {
+            for pairs in pairs.iter_mut() {
+                at = pairs[file].set_sizes(&bytes, at)?;
+            }
+        }
+        if kind == Kind::Dtz {
+            for file in 0..files {
+                let pairs = &mut pairs[0][file];
+                if pairs.flags & MAPPED == 0 {
+                    continue;
+                }
+                if pairs.flags & WIDE != 0 {
+                    at += at & 1;
+                    for i in 0..4 {
+                        pairs.map_idx[i] = at + 2;
+                        at += 2 * le_u16(&bytes, at)? as usize + 2;
+                    }
+                } else {
+                    for i in 0..4 {
+                        pairs.map_idx[i] = at + 1;
+                        at += byte(&bytes, at)? as usize + 1;
+                    }
+                }
+            }
+            at += at & 1;
+        }
+        for file in 0..files {
+            for pairs in pairs.iter_mut() {
+                pairs[file].sparse_index = at;
+                at += 6 * pairs[file].sparse_index_size;
+            }
+        }
+        for file in 0..files {
+            for pairs in pairs.iter_mut() {
+                pairs[file].block_length = at;
+                at += 2 * pairs[file].block_length_size;
+            }
+        }
+        for file in 0..files {
+            for pairs in pairs.iter_mut() {
+                at = (at + 0x3F) & !0x3F;
+                pairs[file].data = at;
+                at += pairs[file].num_blocks * pairs[file].sizeof_block as usize;
+            }
+        }
+        (at <= bytes.len()).then_some(TableFile { bytes, pairs })
+    }
+
+    fn pairs(&self, stm: usize, file: usize) -> &PairsData {
+        &self.pairs[stm % self.pairs.len()][file]
+    }
+
+    fn dtz_score(&self, file: usize, value: i32, wdl: Wdl) -> Option<i32> {
+        const WDL_MAP: [usize; 5] = [1, 3, 0, 2, 0];
+        let pairs = self.pairs(0, file);
+        let flags = pairs.flags;
+        let mut value = value;
+        if flags & MAPPED != 0 {
+            let at = pairs.map_idx[WDL_MAP[(wdl as i32 + 2) as usize]];
+            value = if flags & WIDE != 0 {
+                le_u16(&self.bytes, at + 2 * value as usize)? as i32
+            } else {
+                byte(&self.bytes, at + value as usize)? as i32
+            };
+        }
+        let in_moves = match wdl {
+            Wdl::Win => flags & WIN_PLIES == 0,
+            Wdl::Loss => flags & LOSS_PLIES == 0,
+            _ => true,
+        };
+        Some(if in_moves { value * 2 } else { value } + 1)
+    }
+}
+
+struct Table {
+    material: Material,
+    wdl: Option<PathBuf>,
+    dtz: Option<PathBuf>,
+    wdl_file: OnceLock<Result<TableFile, SyzygyError>>,
+    dtz_file: OnceLock<Result<TableFile, SyzygyError>>,
+}
+
+impl Table {
+    // Files are read whole on first use and kept for the life of the table.
+    fn file(&self, kind: Kind) -> Result<&TableFile, SyzygyError> {
+        let (path, file) = match kind {
+            Kind::Wdl => (&self.wdl, &self.wdl_file),
+            Kind::Dtz => (&self.dtz, &self.dtz_file),
+        };
+        let name = &self.material.name;
+        let Some(path) = path else {
+            return Err(SyzygyError::MissingTable(name.clone()));
+        };
+        file.get_or_init(|| {
+            let bytes = std::fs::read(path).map_err(|err| SyzygyError::Io(err.to_string()))?;
+            TableFile::parse(bytes, &self.material, kind)
+                .ok_or_else(|| SyzygyError::Corrupt(name.clone()))
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+    }
+}
+
+#[derive(Default)]
+pub struct Tablebases {
+    tables: BTreeMap<String, Table>,
+    max_pieces: usize,
+}
+
+impl core::fmt::Debug for Tablebases {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Tablebases")
+            .field("tables", &self.tables.keys().collect::<Vec<_>>())
+            .field("max_pieces", &self.max_pieces)
+            .finish()
+    }
+}
+
+impl Tablebases {
+    pub fn new() -> Self {
+        Tablebases::default()
+    }
+
+    pub fn add_directory<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, SyzygyError> {
+        let io = |err: std::io::Error| SyzygyError::Io(err.to_string());
+        let mut added = 0;
+        for entry in std::fs::read_dir(path).map_err(io)? {
+            let path = entry.map_err(io)?.path();
+            let kind = match path.extension().and_then(|extension| extension.to_str()) {
+                Some("rtbw") => Kind::Wdl,
+                Some("rtbz") => Kind::Dtz,
+                _ => continue,
+            };
+            let Some(material) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(Material::parse)
+            else {
+                continue;
+            };
+            self.max_pieces = self.max_pieces.max(material.piece_count);
+            let table = self
+                .tables
+                .entry(material.name.clone())
+                .or_insert_with(|| Table {
+                    material,
+                    wdl: None,
+                    dtz: None,
+                    wdl_file: OnceLock::new(),
+                    dtz_file: OnceLock::new(),
+                });
+            match kind {
+                Kind::Wdl => (table.wdl, table.wdl_file) = (Some(path), OnceLock::new()),
+                Kind::Dtz => (table.dtz, table.dtz_file) = (Some(path), OnceLock::new()),
+            }
+            added += 1;
+        }
+        Ok(added)
+    }
+
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    fn probe_table(&self, state: &GameState, dtz: Option<Wdl>) -> Result<Option<i32>, SyzygyError> {
+        let (white, black) = (piece_counts(state, White), piece_counts(state, Black));
+        let name = material_name(white, black);
+        if white.iter().chain(&black).sum::<usize>() == 2 {
+            return Ok(Some(0));
+        }
+        let (table, black_stronger) = match self.tables.get(&name) {
+            Some(table) => (table, false),
+            None => match self.tables.get(&material_name(black, white)) {
+                Some(table) => (table, true),
+                None => return Err(SyzygyError::MissingTable(name)),
+            },
+        };
+        let kind = if dtz.is_some() { Kind::Dtz } else { Kind::Wdl };
+        let file = table.file(kind)?;
+        let material = &table.material;
+        let corrupt = || SyzygyError::Corrupt(material.name.clone());
+
+        // Tables are stored with the stronger side as white; a symmetric table
+        // only holds white to move.
+        let black_to_move = state.active_color == Black;
+        let flip = black_stronger || (material.symmetric && black_to_move);
+        let (flip_color, flip_squares) = if flip { (8, 56) } else { (0, 0) };
+        let stm = (flip ^ black_to_move) as usize;
+
+        let indices = indices();
+        let mut squares = [0; MAX_PIECES];
+        let mut pieces = [0; MAX_PIECES];
+        let mut size = 0;
+        let mut lead_pawns = 0;
+        let mut lead_pawns_bitboard = Bitboard::EMPTY;
+        let mut tb_file = 0;
+        if material.has_pawns {
+            let pawn = file.pairs(0, 0).pieces[0] ^ flip_color;
+            let color = if pawn & 8 == 0 { White } else { Black };
+            lead_pawns_bitboard = *state.get_bitboard(Pawn(color));
+            for square in lead_pawns_bitboard.iter_squares() {
+                squares[size] = square ^ flip_squares;
+                size += 1;
+            }
+            lead_pawns = size;
+            let lead = (0..lead_pawns)
+                .max_by_key(|&i| indices.map_pawns[squares[i]])
+                .ok_or_else(corrupt)?;
+            squares.swap(0, lead);
+            tb_file = (squares[0] % 8).min(7 - squares[0] % 8);
+        }
+        if kind == Kind::Dtz {
+            // Symmetric pawnless tables serve both sides to move.
+            let one_sided = material.has_pawns || !material.symmetric;
+            if one_sided && (file.pairs(stm, tb_file).flags & STM) as usize != stm {
+                return Ok(None);
+            }
+        }
+        for (index, bitboard) in state.bitboards.iter().enumerate() {
+            for square in (*bitboard & !lead_pawns_bitboard).iter_squares() {
+                *squares.get_mut(size).ok_or_else(corrupt)? = square ^ flip_squares;
+                pieces[size] = piece_code(index) ^ flip_color;
+                size += 1;
+            }
+        }
+        // The board order above is by piece; the encoder expects the squares
+        // of every group in ascending order, which the sorts below restore.
+        let pairs = file.pairs(stm, tb_file);
+        for i in lead_pawns..size.saturating_sub(1) {
+            if let Some(j) = (i + 1..size).find(|&j| pairs.pieces[i] == pieces[j]) {
+                pieces.swap(i, j);
+                squares.swap(i, j);
+            }
+        }
+        if squares[0] % 8 > 3 {
+            for square in &mut squares[..size] {
+                *square ^= 7;
+            }
+        }
+        let mut idx;
+        if material.has_pawns {
+            idx = indices.lead_pawn_idx[lead_pawns][squares[0]];
+            squares[1..lead_pawns].sort_by_key(|&square| indices.map_pawns[square]);
+            for (i, &square) in squares[..lead_pawns].iter().enumerate().skip(1) {
+                idx += indices.binomial[i][indices.map_pawns[square]];
+            }
+        } else {
+            if squares[0] / 8 > 3 {
+                for square in &mut squares[..size] {
+                    *square ^= 56;
+                }
+            }
+            for i in 0..pairs.group_len[0] {
+                match off_diagonal(squares[i]) {
+                    0 => continue,
+                    offset if offset > 0 => {
+                        for square in &mut squares[i..size] {
+                            *square = flip_diagonal(*square);
+                        }
+                    }
+                    _ => {}
+                }
+                break;
+            }
+            idx = if material.unique_pieces {
+                let adjust1 = (squares[1] > squares[0]) as usize;
+                let adjust2 =
+                    (squares[2] > squares[0]) as usize + (squares[2] > squares[1]) as usize;
+                let rank = |square: usize| (square / 8) as u64;
+                if off_diagonal(squares[0]) != 0 {
+                    ((indices.map_a1d1d4[squares[0]] * 63 + squares[1] - adjust1) * 62 + squares[2]
+                        - adjust2) as u64
+                } else if off_diagonal(squares[1]) != 0 {
+                    (6 * 63 + rank(squares[0]) * 28 + indices.map_b1h1h7[squares[1]] as u64) * 62
+                        + (squares[2] - adjust2) as u64
+                } else if off_diagonal(squares[2]) != 0 {
+                    6 * 63 * 62
+                        + 4 * 28 * 62
+                        + rank(squares[0]) * 7 * 28
+                        + (rank(squares[1]) - adjust1 as u64) * 28
+                        + indices.map_b1h1h7[squares[2]] as u64
+                } else {
+                    6 * 63 * 62
+                        + 4 * 28 * 62
+                        + 4 * 7 * 28
+                        + rank(squares[0]) * 7 * 6
+                        + (rank(squares[1]) - adjust1 as u64) * 6
+                        + (rank(squares[2]) - adjust2 as u64)
+                }
+            } else {
+                indices.map_kk[indices.map_a1d1d4[squares[0]]][squares[1]] as u64
+            };
+        }
+        idx *= pairs.group_idx[0];
+        let mut start = pairs.group_len[0];
+        let mut remaining_pawns = material.has_pawns && material.pawn_count[1] > 0;
+        let mut next = 1;
+        while pairs.group_len[next] != 0 {
+            let len = pairs.group_len[next];
+            squares[start..start + len].sort_unstable();
+            let mut n = 0;
+            for i in 0..len {
+                let square = squares[start + i];
+                let adjust = squares[..start].iter().filter(|&&s| square > s).count();
+                let available = (square - adjust)
+                    .checked_sub(8 * remaining_pawns as usize)
+                    .ok_or_else(corrupt)?;
+                n += indices.binomial[i + 1][available];
+            }
+            remaining_pawns = false;
+            idx += n * pairs.group_idx[next];
+            start += len;
+            next += 1;
+        }
+        let value = pairs.decompress(&file.bytes, idx).ok_or_else(corrupt)?;
+        Ok(Some(match dtz {
+            None => value - 2,
+            Some(wdl) => file.dtz_score(tb_file, value, wdl).ok_or_else(corrupt)?,
+        }))
+    }
+
+    // Captures are not stored when they decide the result, so they are
+    // searched here. The flag is set when the best move resets the fifty-move
+    // counter, in which case the DTZ table cannot be trusted.
+    fn search(&self, state: &mut GameState, zeroing: bool) -> Result<(Wdl, bool), SyzygyError> {
+        let moves: Vec<Move> = state.legal_moves_iter().collect();
+        let mut best = Wdl::Loss;
+        let mut searched = 0;
+        for &mv in &moves {
+            let pawn = matches!(state.get_piece_on(mv.from()), Some(Pawn(_)));
+            if !(mv.is_capture() || zeroing && pawn) {
+                continue;
+            }
+            searched += 1;
+            let undo = state.make_move(mv).map_err(SyzygyError::Corrupt)?;
+            let value = self.search(state, false);
+            state.unmake_move(mv, undo);
+            let value = -value?.0;
+            if value > best {
+                best = value;
+                if value >= Wdl::Win {
+                    return Ok((value, true));
+                }
+            }
+        }
+        let no_more_moves = searched > 0 && searched == moves.len();
+        let value = if no_more_moves {
+            best
+        } else {
+            let value = self.probe_table(state, None)?.unwrap_or_default();
+            Wdl::from_value(value)
+        };
+        if best >= value {
+            Ok((best, best > Wdl::Draw || no_more_moves))
+        } else {
+            Ok((value, false))
+        }
+    }
+
+    fn dtz(&self, state: &mut GameState) -> Result<i32, SyzygyError> {
+        let (wdl, zeroing) = self.search(state, true)?;
+        if wdl == Wdl::Draw {
+            return Ok(0);
+        }
+        if zeroing {
+            return Ok(dtz_before_zeroing(wdl));
+        }
+        if let Some(dtz) = self.probe_table(state, Some(wdl))? {
+            let cursed = matches!(wdl, Wdl::BlessedLoss | Wdl::CursedWin);
+            return Ok((dtz + 100 * cursed as i32) * wdl.signum());
+        }
+        // The table only covers the other side to move, so look one ply ahead.
+        let mut min_dtz = i32::MAX;
+        let moves: Vec<Move> = state.legal_moves_iter().collect();
+        for mv in moves {
+            let zeroing = mv.is_capture() || matches!(state.get_piece_on(mv.from()), Some(Pawn(_)));
+            let undo = state.make_move(mv).map_err(SyzygyError::Corrupt)?;
+            let dtz = if zeroing {
+                self.search(state, false)
+                    .map(|(wdl, _)| -dtz_before_zeroing(wdl))
+            } else {
+                self.dtz(state).map(|dtz| -dtz)
+            };
+            let mates = state.is_checkmate();
+            state.unmake_move(mv, undo);
+            let mut dtz = dtz?;
+            if dtz == 1 && mates {
+                min_dtz = 1;
+            }
+            if !zeroing {
+                dtz += dtz.signum();
+            }
+            if dtz < min_dtz && dtz.signum() == wdl.signum() {
+                min_dtz = dtz;
+            }
+        }
+        Ok(if min_dtz == i32::MAX { -1 } else { min_dtz })
+    }
+
+    fn check(&self, state: &GameState) -> Result<(), SyzygyError> {
+        if state.castling_rights.iter().any(|&right| right) {
+            return Err(SyzygyError::Castling);
+        }
+        Ok(())
+    }
+}
+
+fn dtz_before_zeroing(wdl: Wdl) -> i32 {
+    match wdl {
+        Wdl::Win => 1,
+        Wdl::CursedWin => 101,
+        Wdl::BlessedLoss => -101,
+        Wdl::Loss => -1,
+        Wdl::Draw => 0,
+    }
+}
+
+impl GameState {
+    pub fn probe_wdl(&self, tablebases: &Tablebases) -> Result<Wdl, SyzygyError> {
+        tablebases.check(self)?;
+        Ok(tablebases.search(&mut self.clone(), false)?.0)
+    }
+
+    // Plies to the next capture or pawn move under optimal play, positive when
+    // the side to move wins. Like the tables themselves it may be one ply off.
+    pub fn probe_dtz(&self, tablebases: &Tablebases) -> Result<i32, SyzygyError> {
+        tablebases.check(self)?;
+        tablebases.dtz(&mut self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{format, vec, vec::Vec};
+    use std::{fs, path::PathBuf};
+
+    use super::{DTZ_MAGIC, WDL_MAGIC};
+    use crate::{
+        Color::*, GameState, GameStateBuilder, Piece::*, Square, SyzygyError, Tablebases, Wdl,
+    };
+
+    type Symmetry = fn(usize, usize) -> (usize, usize);
+
+    // Data blocks start on the next 64 byte boundary.
+    fn table(magic: [u8; 4], header: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut bytes = magic.to_vec();
+        bytes.extend_from_slice(header);
+        bytes.resize(bytes.len().next_multiple_of(64), 0);
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    // Tables are read on first probe, so the directory lives as long as the
+    // test holds on to it.
+    struct TableDir(PathBuf);
+
+    impl Drop for TableDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn tablebases(name: &str, files: &[(&str, Vec<u8>)]) -> (Tablebases, TableDir) {
+        let dir =
+            std::env::temp_dir().join(format!("chersed-syzygy-{}-{name}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (file, bytes) in files {
+            fs::write(dir.join(file), bytes).unwrap();
+        }
+        let mut tablebases = Tablebases::new();
+        assert_eq!(tablebases.add_directory(&dir), Ok(files.len()));
+        (tablebases, TableDir(dir))
+    }
+
+    // KQvK with every white to move position won and every black to move
+    // position lost, which the DTZ table puts five moves from zeroing.
+    fn single_value_kqvk() -> (Tablebases, TableDir) {
+        let pieces = [0x01, 0x00, 0x66, 0x55, 0xEE, 0x00];
+        let wdl = [&pieces[..], &[0x80, 4, 0x80, 0]].concat();
+        let dtz = [&pieces[..], &[0x80, 5]].concat();
+        tablebases(
+            "kqvk",
+            &[
+                ("KQvK.rtbw", table(WDL_MAGIC, &wdl, &[])),
+                ("KQvK.rtbz", table(DTZ_MAGIC, &dtz, &[])),
+            ],
+        )
+    }
+
+    fn probe_wdl(tablebases: &Tablebases, fen: &str) -> Result<Wdl, SyzygyError> {
+        fen.parse::<GameState>().unwrap().probe_wdl(tablebases)
+    }
+
+    fn probe_dtz(tablebases: &Tablebases, fen: &str) -> Result<i32, SyzygyError> {
+        fen.parse::<GameState>().unwrap().probe_dtz(tablebases)
+    }
+
+    #[test]
+    fn probe_wdl_single_value() {
+        let (tablebases, _dir) = single_value_kqvk();
+        assert_eq!(tablebases.max_pieces(), 3);
+        assert_eq!(
+            probe_wdl(&tablebases, "4k3/8/8/8/8/8/8/KQ6 w - - 0 1"),
+            Ok(Wdl::Win)
+        );
+        assert_eq!(
+            probe_wdl(&tablebases, "4k3/8/8/8/8/8/8/KQ6 b - - 0 1"),
+            Ok(Wdl::Loss)
+        );
+        assert_eq!(
+            probe_wdl(&tablebases, "4k3/4Q3/8/8/8/8/8/K7 b - - 0 1"),
+            Ok(Wdl::Draw)
+        );
+        let flipped = "4k3/8/8/8/8/8/8/KQ6 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap()
+            .flipped();
+        assert_eq!(flipped.probe_wdl(&tablebases), Ok(Wdl::Win));
+    }
+
+    #[test]
+    fn probe_errors() {
+        let (tablebases, _dir) = single_value_kqvk();
+        assert_eq!(
+            probe_wdl(&Tablebases::new(), "8/8/8/8/8/8/8/K6k w - - 0 1"),
+            Ok(Wdl::Draw)
+        );
+        assert_eq!(
+            probe_wdl(&tablebases, "8/8/8/8/8/8/8/KR5k w - - 0 1"),
+            Err(SyzygyError::MissingTable("KRvK".into()))
+        );
+        assert_eq!(
+            probe_dtz(&tablebases, "4k3/8/8/8/8/8/8/R3K3 w Q - 0 1"),
+            Err(SyzygyError::Castling)
+        );
+    }
+
+    #[test]
+    fn probe_dtz_single_value() {
+        let (tablebases, _dir) = single_value_kqvk();
+        assert_eq!(
+            probe_dtz(&tablebases, "4k3/8/8/8/8/8/8/KQ6 w - - 0 1"),
+            Ok(11)
+        );
+        assert_eq!(
+            probe_dtz(&tablebases, "4k3/8/8/8/8/8/8/KQ6 b - - 0 1"),
+            Ok(-12)
+        );
+        assert_eq!(
+            probe_dtz(&tablebases, "4k3/4Q3/8/8/8/8/8/K7 b - - 0 1"),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn probe_wdl_canonical_index() {
+        // A single Huffman block where each bit is the result of one index:
+        // symbol 0 is a win and symbol 1 a draw.
+        let mut header = vec![
+            0x01, 0x00, 0x66, 0x55, 0xEE, 0x00, // flags, order, pieces, padding
+            0x00, 6, 9, 0, 62, 0, 0, 0, 1, 1, 0, 0, 2, 0, // white to move sizes
+            4, 0xF0, 0xFF, 2, 0xF0, 0xFF, // symbol tree
+            0x80, 0, // black to move
+        ];
+        // Every index is one bit, so each 64 byte block holds 512 of them and
+        // sparse entry k points into the middle of block k.
+        for block in 0..62_u32 {
+            header.extend_from_slice(&block.to_le_bytes());
+            header.extend_from_slice(&256_u16.to_le_bytes());
+        }
+        for block in 0..62 {
+            let length: u16 = if block < 61 { 511 } else { 99 };
+            header.extend_from_slice(&length.to_le_bytes());
+        }
+        let mut seed = 0x9E37_79B9_7F4A_7C15_u64;
+        let data: Vec<u8> = (0..62 * 64)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as u8
+            })
+            .collect();
+        let (tablebases, _dir) = tablebases(
+            "canonical",
+            &[("KQvK.rtbw", table(WDL_MAGIC, &header, &data))],
+        );
+
+        let symmetries: [Symmetry; 8] = [
+            |file, rank| (file, rank),
+            |file, rank| (7 - file, rank),
+            |file, rank| (file, 7 - rank),
+            |file, rank| (7 - file, 7 - rank),
+            |file, rank| (rank, file),
+            |file, rank| (7 - rank, file),
+            |file, rank| (rank, 7 - file),
+            |file, rank| (7 - rank, 7 - file),
+        ];
+        let mut seen = vec![];
+        for (white_king, queen, black_king) in (0..64).step_by(7).flat_map(|wk| {
+            (0..64)
+                .step_by(9)
+                .flat_map(move |q| (0..64).step_by(4).map(move |bk| (wk, q, bk)))
+        }) {
+            let results: Vec<Wdl> = symmetries
+                .iter()
+                .filter_map(|symmetry| {
+                    let square = |square: usize| {
+                        let (file, rank) = symmetry(square % 8, square / 8);
+                        Square::from_index(rank * 8 + file).unwrap()
+                    };
+                    let state = GameStateBuilder::new()
+                        .place(square(white_king), King(White))
+                        .place(square(queen), Queen(White))
+                        .place(square(black_king), King(Black))
+                        .build()
+                        .ok()
+                        .filter(|state| {
+                            state.count(King(White)) == 1 && !state.is_in_check(Black)
+                        })?;
+                    let wdl = state.probe_wdl(&tablebases).unwrap();
+                    assert_eq!(state.flipped().probe_wdl(&tablebases), Ok(wdl));
+                    Some(wdl)
+                })
+                .collect();
+            assert!(results.windows(2).all(|pair| pair[0] == pair[1]));
+            seen.extend(results.first());
+        }
+        assert!(seen.contains(&Wdl::Win));
+        assert!(seen.contains(&Wdl::Draw));
+    }
+
+    #[test]
+    fn probe_wdl_pawns() {
+        // The a and h files are drawn, the rest won for white.
+        let mut header = vec![0x03];
+        for _ in 0..4 {
+            header.extend_from_slice(&[0x00, 0x11, 0x66, 0xEE]);
+        }
+        header.push(0x00);
+        header.extend_from_slice(&[0x80, 2, 0x80, 2]);
+        for _ in 1..4 {
+            header.extend_from_slice(&[0x80, 4, 0x80, 0]);
+        }
+        let (tablebases, _dir) =
+            tablebases("kpvk", &[("KPvK.rtbw", table(WDL_MAGIC, &header, &[]))]);
+        assert_eq!(
+            probe_wdl(&tablebases, "4k3/8/8/8/8/8/P7/K7 w - - 0 1"),
+            Ok(Wdl::Draw)
+        );
+        assert_eq!(
+            probe_wdl(&tablebases, "4k3/8/8/8/8/8/7P/K7 w - - 0 1"),
+            Ok(Wdl::Draw)
+        );
+        assert_eq!(
+            probe_wdl(&tablebases, "k7/8/8/8/8/8/3P4/4K3 w - - 0 1"),
+            Ok(Wdl::Win)
+        );
+        assert_eq!(
+            probe_wdl(&tablebases, "k7/8/8/8/8/8/4P3/3K4 w - - 0 1"),
+            Ok(Wdl::Win)
+        );
+        assert_eq!(
+            probe_wdl(&tablebases, "k7/8/8/8/8/8/3P4/4K3 b - - 0 1"),
+            Ok(Wdl::Loss)
+        );
+        let flipped = "k7/8/8/8/8/8/3P4/4K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap()
+            .flipped();
+        assert_eq!(flipped.probe_wdl(&tablebases), Ok(Wdl::Win));
+    }
+
+    // The other tests only use synthetic tables. This one checks the decoder
+    // against real files: point SYZYGY_PATH at a directory holding KQvK and
+    // KRvK and run it with --ignored.
+    #[test]
+    #[ignore = "needs real Syzygy tables in SYZYGY_PATH"]
+    fn probe_real_tables() {
+        let Ok(path) = std::env::var("SYZYGY_PATH") else {
+            return;
+        };
+        let mut tablebases = Tablebases::new();
+        tablebases.add_directory(path).unwrap();
+
+        assert_eq!(
+            probe_wdl(&tablebases, "4k3/8/8/8/8/8/8/KQ6 w - - 0 1"),
+            Ok(Wdl::Win)
+        );
+        assert_eq!(
+            probe_wdl(&tablebases, "4k3/8/8/8/8/8/8/KQ6 b - - 0 1"),
+            Ok(Wdl::Loss)
+        );
+        // stalemate and an undefended queen are both draws
+        assert_eq!(
+            probe_wdl(&tablebases, "k7/8/1Q6/8/8/8/8/7K b - - 0 1"),
+            Ok(Wdl::Draw)
+        );
+        assert_eq!(
+            probe_dtz(&tablebases, "k7/8/1Q6/8/8/8/8/7K b - - 0 1"),
+            Ok(0)
+        );
+        assert_eq!(
+            probe_wdl(&tablebases, "4k3/4Q3/8/8/8/8/8/K7 b - - 0 1"),
+            Ok(Wdl::Draw)
+        );
+        // Qh8 mates at once
+        assert_eq!(
+            probe_dtz(&tablebases, "k7/8/1K6/8/8/8/8/7Q w - - 0 1"),
+            Ok(1)
+        );
+        let dtz = probe_dtz(&tablebases, "4k3/8/8/8/8/8/8/KQ6 w - - 0 1").unwrap();
+        assert!((2..=25).contains(&dtz), "{dtz}");
+        let dtz = probe_dtz(&tablebases, "4k3/8/8/8/8/8/8/KQ6 b - - 0 1").unwrap();
+        assert!((-25..=-2).contains(&dtz), "{dtz}");
+
+        assert_eq!(
+            probe_wdl(&tablebases, "4k3/8/8/8/8/8/8/R3K3 w - - 0 1"),
+            Ok(Wdl::Win)
+        );
+        assert_eq!(
+            probe_wdl(&tablebases, "8/8/8/8/8/8/1k6/R6K b - - 0 1"),
+            Ok(Wdl::Draw)
+        );
+        let flipped = "4k3/8/8/8/8/8/8/R3K3 w - - 0 1"
+            .parse::<GameState>()
+            .unwrap()
+            .flipped();
+        assert_eq!(flipped.probe_wdl(&tablebases), Ok(Wdl::Win));
+        let dtz = probe_dtz(&tablebases, "4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert!((2..=40).contains(&dtz), "{dtz}");
+    }
+}
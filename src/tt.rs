@@ -0,0 +1,296 @@
+use alloc::vec::Vec;
+use core::{
+    fmt::Debug,
+    sync::atomic::{AtomicU32, AtomicU8, Ordering::Relaxed},
+};
+
+use crate::Move;
+
+pub const DEFAULT_HASH_MB: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub key: u64,
+    pub best_move: Option<Move>,
+    pub score: i32,
+    pub depth: u8,
+    pub bound: Bound,
+    generation: u8,
+}
+
+impl Entry {
+    fn pack(&self) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        };
+        self.best_move.map_or(0, |mv| mv.bits() as u64)
+            | (self.score as i16 as u16 as u64) << 16
+            | (self.depth as u64) << 32
+            | bound << 40
+            | (self.generation as u64) << 48
+    }
+
+    fn unpack(key: u64, data: u64) -> Option<Entry> {
+        let bound = match data >> 40 & 0xFF {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        let best_move = data as u16;
+        Some(Entry {
+            key,
+            best_move: (best_move != 0).then(|| Move::from_bits(best_move)),
+            score: (data >> 16) as u16 as i16 as i32,
+            depth: (data >> 32) as u8,
+            bound,
+            generation: (data >> 48) as u8,
+        })
+    }
+}
+
+// A slot holds the packed entry and its key XORed with it, split into 32-bit
+// words so it stays lock-free on targets without 64-bit atomics. A read torn
+// by a concurrent store fails the key check and is treated as a miss.
+#[derive(Default)]
+struct Slot {
+    check: [AtomicU32; 2],
+    data: [AtomicU32; 2],
+}
+
+fn load(words: &[AtomicU32; 2]) -> u64 {
+    words[0].load(Relaxed) as u64 | (words[1].load(Relaxed) as u64) << 32
+}
+
+fn save(words: &[AtomicU32; 2], value: u64) {
+    words[0].store(value as u32, Relaxed);
+    words[1].store((value >> 32) as u32, Relaxed);
+}
+
+impl Slot {
+    fn get(&self) -> Option<Entry> {
+        let data = load(&self.data);
+        Entry::unpack(load(&self.check) ^ data, data)
+    }
+
+    fn set(&self, entry: Option<Entry>) {
+        let (key, data) = entry.map_or((0, 0), |entry| (entry.key, entry.pack()));
+        save(&self.data, data);
+        save(&self.check, key ^ data);
+    }
+}
+
+pub struct TranspositionTable {
+    slots: Vec<Slot>,
+    generation: AtomicU8,
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        TranspositionTable::new(DEFAULT_HASH_MB)
+    }
+}
+
+impl Clone for TranspositionTable {
+    fn clone(&self) -> Self {
+        let slots = self
+            .slots
+            .iter()
+            .map(|slot| {
+                let copy = Slot::default();
+                copy.set(slot.get());
+                copy
+            })
+            .collect();
+        TranspositionTable {
+            slots,
+            generation: AtomicU8::new(self.generation()),
+        }
+    }
+}
+
+impl Debug for TranspositionTable {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TranspositionTable")
+            .field("capacity", &self.capacity())
+            .field("generation", &self.generation())
+            .finish()
+    }
+}
+
+impl TranspositionTable {
+    pub fn new(megabytes: usize) -> Self {
+        let capacity = (megabytes * 1024 * 1024 / core::mem::size_of::<Slot>()).max(1);
+        TranspositionTable {
+            slots: (0..capacity).map(|_| Slot::default()).collect(),
+            generation: AtomicU8::new(0),
+        }
+    }
+
+    pub fn resize(&mut self, megabytes: usize) {
+        *self = TranspositionTable::new(megabytes);
+    }
+
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            slot.set(None);
+        }
+        self.generation.store(0, Relaxed);
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(|slot| slot.get().is_none())
+    }
+
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Relaxed);
+    }
+
+    fn generation(&self) -> u8 {
+        self.generation.load(Relaxed)
+    }
+
+    fn slot(&self, key: u64) -> &Slot {
+        &self.slots[(key % self.slots.len() as u64) as usize]
+    }
+
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        self.slot(key).get().filter(|entry| entry.key == key)
+    }
+
+    pub fn store(&self, key: u64, best_move: Option<Move>, score: i32, depth: u8, bound: Bound) {
+        let slot = self.slot(key);
+        let generation = self.generation();
+        let current = slot.get();
+        let replace = match current {
+            None => true,
+            Some(entry) => {
+                entry.key == key || entry.generation != generation || depth >= entry.depth
+            }
+        };
+        if replace {
+            let best_move = best_move.or_else(|| {
+                current
+                    .filter(|entry| entry.key == key)
+                    .and_then(|entry| entry.best_move)
+            });
+            slot.set(Some(Entry {
+                key,
+                best_move,
+                score,
+                depth,
+                bound,
+                generation,
+            }));
+        }
+    }
+
+    pub fn hashfull(&self) -> usize {
+        let sample = self.slots.len().min(1000);
+        let generation = self.generation();
+        let used = self.slots[..sample]
+            .iter()
+            .filter(|slot| {
+                slot.get()
+                    .is_some_and(|entry| entry.generation == generation)
+            })
+            .count();
+        used * 1000 / sample
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        tt::{Bound, TranspositionTable},
+        Move,
+    };
+
+    #[test]
+    fn store_and_probe() {
+        let tt = TranspositionTable::new(1);
+        assert!(tt.capacity() > 1000);
+        assert!(tt.is_empty());
+        let mv = Move::new(12, 28, None);
+        tt.store(42, Some(mv), 15, 3, Bound::Exact);
+        let entry = tt.probe(42).unwrap();
+        assert_eq!(Some(mv), entry.best_move);
+        assert_eq!(
+            (15, 3, Bound::Exact),
+            (entry.score, entry.depth, entry.bound)
+        );
+        assert_eq!(None, tt.probe(43));
+        assert_eq!(None, tt.probe(42 + tt.capacity() as u64));
+        tt.clear();
+        assert_eq!(None, tt.probe(42));
+    }
+
+    #[test]
+    fn replacement_prefers_depth_within_a_search() {
+        let mut tt = TranspositionTable::new(1);
+        let other = 7 + tt.capacity() as u64;
+        tt.store(7, None, 1, 6, Bound::Lower);
+        tt.store(other, None, 2, 2, Bound::Lower);
+        assert_eq!(6, tt.probe(7).unwrap().depth);
+        assert_eq!(None, tt.probe(other));
+        tt.new_search();
+        tt.store(other, None, 2, 2, Bound::Upper);
+        assert_eq!(Bound::Upper, tt.probe(other).unwrap().bound);
+        let mv = Move::new(6, 21, None);
+        tt.store(other, Some(mv), 3, 4, Bound::Lower);
+        tt.store(other, None, 4, 5, Bound::Upper);
+        assert_eq!(Some(mv), tt.probe(other).unwrap().best_move);
+        tt.resize(2);
+        assert!(tt.is_empty());
+    }
+
+    #[test]
+    fn entries_round_trip_packing() {
+        let tt = TranspositionTable::new(1);
+        let mv = Move::new_capture(52, 61, Some(crate::Piece::Knight(crate::Color::White)));
+        tt.store(u64::MAX, Some(mv), -29_990, u8::MAX, Bound::Upper);
+        let entry = tt.probe(u64::MAX).unwrap();
+        assert_eq!(mv.bits(), entry.best_move.unwrap().bits());
+        assert_eq!(
+            (-29_990, u8::MAX, Bound::Upper),
+            (entry.score, entry.depth, entry.bound)
+        );
+        assert_eq!(0, tt.hashfull());
+        tt.store(0, None, 0, 0, Bound::Exact);
+        assert_eq!(1, tt.hashfull());
+        tt.new_search();
+        assert_eq!(0, tt.hashfull());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn shared_between_threads() {
+        let tt = TranspositionTable::new(1);
+        std::thread::scope(|scope| {
+            for thread in 0..4_u64 {
+                let tt = &tt;
+                scope.spawn(move || {
+                    for key in (thread * 100)..(thread * 100 + 100) {
+                        tt.store(key, None, key as i32, 1, Bound::Exact);
+                    }
+                });
+            }
+        });
+        for key in 0..400 {
+            assert_eq!(key as i32, tt.probe(key).unwrap().score);
+        }
+    }
+}
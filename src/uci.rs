@@ -0,0 +1,461 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use crate::{
+    search::{search_with_stop, SearchLimits, SearchResult, MATE_SCORE},
+    square_name,
+    tt::{TranspositionTable, DEFAULT_HASH_MB},
+    Color, DefaultEvaluator, GameState, Move, MoveParseError, Piece,
+};
+
+const DEFAULT_DEPTH: u32 = 4;
+const MAX_HASH_MB: usize = 1024;
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+#[cfg(feature = "std")]
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+pub fn uci_string(from: usize, to: usize, promotion: Option<Piece>) -> String {
+    let mut uci = format!("{}{}", square_name(from), square_name(to));
+    if let Some(promotion) = promotion {
+        uci.push_str(&promotion.fen().to_lowercase());
+    }
+    uci
+}
+
+impl GameState {
+    pub fn parse_uci(&self, uci: &str) -> Result<Move, MoveParseError> {
+        let mv: Move = uci.parse()?;
+        Ok(self
+            .legal_moves_iter()
+            .find(|&legal| legal == mv)
+            .unwrap_or(mv))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct UciEngine {
+    position: GameState,
+    options: Vec<(String, String)>,
+    tt: Arc<TranspositionTable>,
+    stop: Arc<AtomicBool>,
+    infinite: bool,
+    #[cfg(feature = "std")]
+    search: Option<std::thread::JoinHandle<Vec<String>>>,
+    #[cfg(not(feature = "std"))]
+    finished: Vec<String>,
+}
+
+// Spends an even share of the remaining time over the moves left to the next
+// time control, plus most of the increment, and never more than half the clock.
+fn allocate_time(remaining: Duration, increment: Duration, moves_to_go: Option<u32>) -> Duration {
+    let moves = moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+    (remaining / moves + increment * 3 / 4).min(remaining / 2)
+}
+
+fn report(result: &SearchResult, hashfull: usize) -> Vec<String> {
+    let score = if result.score.abs() >= MATE_SCORE - 100 {
+        let moves = (MATE_SCORE - result.score.abs() + 1) / 2;
+        format!("mate {}", moves * result.score.signum())
+    } else {
+        format!("cp {}", result.score)
+    };
+    let mut info = format!(
+        "info depth {} score {score} nodes {} hashfull {hashfull} pv",
+        result.depth, result.nodes
+    );
+    for mv in &result.pv {
+        info.push_str(&format!(" {mv}"));
+    }
+    let best_move = result
+        .best_move
+        .map_or("0000".to_string(), |mv| mv.to_string());
+    vec![info, format!("bestmove {best_move}")]
+}
+
+impl UciEngine {
+    pub fn position(&self) -> &GameState {
+        &self.position
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(option, _)| option.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn handle(&mut self, command: &str) -> Vec<String> {
+        let mut tokens = command.split_whitespace();
+        match tokens.next() {
+            Some("uci") => vec![
+                format!("id name chersed {}", env!("CARGO_PKG_VERSION")),
+                "id author chersed contributors".to_string(),
+                format!(
+                    "option name Hash type spin default {DEFAULT_HASH_MB} min 1 max {MAX_HASH_MB}"
+                ),
+                "uciok".to_string(),
+            ],
+            Some("isready") => vec!["readyok".to_string()],
+            Some("ucinewgame") => {
+                self.position = GameState::default();
+                self.tt.clear();
+                vec![]
+            }
+            Some("position") => match self.set_position(tokens.collect()) {
+                Ok(()) => vec![],
+                Err(err) => vec![format!("info string {err}")],
+            },
+            Some("setoption") => {
+                let rest: Vec<&str> = tokens.collect();
+                let rest = rest.join(" ");
+                let Some(rest) = rest.strip_prefix("name ") else {
+                    return vec!["info string setoption requires a name".to_string()];
+                };
+                let (name, value) = rest.split_once(" value ").unwrap_or((rest, ""));
+                self.options
+                    .retain(|(option, _)| !option.eq_ignore_ascii_case(name));
+                self.options.push((name.to_string(), value.to_string()));
+                if name.eq_ignore_ascii_case("Hash") {
+                    match value.parse::<usize>() {
+                        Ok(megabytes) => {
+                            let megabytes = megabytes.clamp(1, MAX_HASH_MB);
+                            self.tt = Arc::new(TranspositionTable::new(megabytes));
+                        }
+                        Err(_) => return vec![format!("info string Invalid Hash size {value}")],
+                    }
+                }
+                vec![]
+            }
+            Some("go") => self.go(tokens.collect()),
+            Some("stop") => self.stop(),
+            _ => vec![],
+        }
+    }
+
+    fn set_position(&mut self, tokens: Vec<&str>) -> Result<(), String> {
+        let split = tokens
+            .iter()
+            .position(|&token| token == "moves")
+            .unwrap_or(tokens.len());
+        let (setup, moves) = tokens.split_at(split);
+        let start = match setup {
+            ["startpos"] => GameState::default(),
+            ["fen", fen @ ..] => fen
+                .join(" ")
+                .parse::<GameState>()
+                .map_err(|err| format!("Invalid FEN: {err}"))?,
+            _ => return Err("Expected startpos or fen".to_string()),
+        };
+        let moves = moves.get(1..).unwrap_or_default();
+        self.position = start.play_moves(moves)?;
+        Ok(())
+    }
+
+    fn go(&mut self, tokens: Vec<&str>) -> Vec<String> {
+        let responses = self.stop();
+        let (time, increment) = match self.position.active_color {
+            Color::White => ("wtime", "winc"),
+            Color::Black => ("btime", "binc"),
+        };
+        let millis = |name: &str| {
+            tokens
+                .windows(2)
+                .find(|pair| pair[0] == name)
+                .and_then(|pair| pair[1].parse().ok())
+                .map(Duration::from_millis)
+        };
+        let mut limits = SearchLimits::default();
+        for pair in tokens.windows(2) {
+            match pair {
+                ["depth", depth] => limits.depth = depth.parse().ok(),
+                ["nodes", nodes] => limits.nodes = nodes.parse().ok(),
+                ["movetime", movetime] => {
+                    limits.movetime = movetime.parse().ok().map(Duration::from_millis)
+                }
+                _ => {}
+            }
+        }
+        if let (None, Some(remaining)) = (limits.movetime, millis(time)) {
+            let moves_to_go = tokens
+                .windows(2)
+                .find(|pair| pair[0] == "movestogo")
+                .and_then(|pair| pair[1].parse().ok());
+            limits.movetime = Some(allocate_time(
+                remaining,
+                millis(increment).unwrap_or_default(),
+                moves_to_go,
+            ));
+        }
+        let infinite = tokens.contains(&"infinite");
+        self.infinite = infinite;
+        // Without std there is neither a clock nor a stop command, so only
+        // depth and node limits can end the search.
+        let timed = cfg!(feature = "std") && (limits.movetime.is_some() || infinite);
+        if limits.depth.is_none() && limits.nodes.is_none() && !timed {
+            limits.depth = Some(DEFAULT_DEPTH);
+        }
+        self.stop.store(false, Ordering::Relaxed);
+        let position = self.position.clone();
+        let tt = Arc::clone(&self.tt);
+        let stop = Arc::clone(&self.stop);
+        let search = move || {
+            let result = search_with_stop(&position, limits, &DefaultEvaluator, &tt, &stop);
+            // UCI forbids sending bestmove for an infinite search before stop.
+            #[cfg(feature = "std")]
+            while infinite && !stop.load(Ordering::Relaxed) {
+                std::thread::park();
+            }
+            report(&result, tt.hashfull())
+        };
+        #[cfg(feature = "std")]
+        {
+            self.search = Some(std::thread::spawn(search));
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.finished = search();
+        }
+        responses
+    }
+
+    pub fn stop(&mut self) -> Vec<String> {
+        self.stop.store(true, Ordering::Relaxed);
+        #[cfg(feature = "std")]
+        if let Some(search) = &self.search {
+            search.thread().unpark();
+        }
+        self.wait()
+    }
+
+    pub fn poll(&mut self) -> Vec<String> {
+        #[cfg(feature = "std")]
+        if !self
+            .search
+            .as_ref()
+            .is_some_and(std::thread::JoinHandle::is_finished)
+        {
+            return vec![];
+        }
+        self.wait()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn wait(&mut self) -> Vec<String> {
+        self.search.take().map_or_else(Vec::new, |search| {
+            search.join().expect("search thread panicked")
+        })
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn wait(&mut self) -> Vec<String> {
+        core::mem::take(&mut self.finished)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn run(
+        &mut self,
+        input: impl std::io::BufRead + Send,
+        mut output: impl std::io::Write,
+    ) -> std::io::Result<()> {
+        use std::sync::mpsc::{channel, RecvTimeoutError};
+
+        let (sender, receiver) = channel();
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                for line in input.lines() {
+                    let last = line.as_ref().map_or(true, |line| line.trim() == "quit");
+                    if sender.send(line).is_err() || last {
+                        break;
+                    }
+                }
+            });
+            loop {
+                let (responses, done) = match receiver.recv_timeout(POLL_INTERVAL) {
+                    Ok(line) => match line?.trim() {
+                        "quit" => (self.stop(), true),
+                        line => (self.handle(line), false),
+                    },
+                    Err(RecvTimeoutError::Timeout) => (self.poll(), false),
+                    // Input ran out, so let a bounded search finish on its own.
+                    Err(RecvTimeoutError::Disconnected) if !self.infinite => (self.wait(), true),
+                    Err(RecvTimeoutError::Disconnected) => (self.stop(), true),
+                };
+                for response in responses {
+                    writeln!(output, "{response}")?;
+                }
+                output.flush()?;
+                if done {
+                    return Ok(());
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+    use core::time::Duration;
+
+    use crate::{
+        tt::TranspositionTable, uci::allocate_time, uci_string, Color::*, GameState, Move,
+        Piece::*, UciEngine,
+    };
+
+    #[test]
+    fn parse_uci_moves() {
+        let game_state = GameState::default();
+        assert_eq!(Ok(Move::new(12, 28, None)), game_state.parse_uci("e2e4"));
+        assert_eq!(Ok(Move::new(4, 6, None)), game_state.parse_uci("e1g1"));
+        let game_state = "4k3/8/8/8/8/8/p7/4K3 b - - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        assert_eq!(
+            Ok(Move::new(8, 0, Some(Queen(Black)))),
+            game_state.parse_uci("a2a1q")
+        );
+    }
+
+    #[test]
+    fn parse_uci_errors() {
+        let game_state = GameState::default();
+        assert!(game_state.parse_uci("z9e4").is_err());
+        assert!(game_state.parse_uci("e2e").is_err());
+        assert!(game_state.parse_uci("e7e8k").is_err());
+        assert!(game_state.parse_uci("e7e8qq").is_err());
+    }
+
+    #[test]
+    fn uci_string_formatting() {
+        assert_eq!("e2e4", uci_string(12, 28, None));
+        assert_eq!("e7e8q", uci_string(52, 60, Some(Queen(White))));
+        assert_eq!("e1g1", uci_string(4, 6, None));
+    }
+
+    #[test]
+    fn uci_engine_handshake_and_position() {
+        let mut engine = UciEngine::default();
+        assert_eq!(
+            Some("uciok"),
+            engine.handle("uci").last().map(|line| line.as_str())
+        );
+        assert_eq!(vec!["readyok"], engine.handle("isready"));
+        assert!(engine
+            .handle("position startpos moves e2e4 e7e5 g1f3")
+            .is_empty());
+        assert_eq!(
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2",
+            engine.position().to_string()
+        );
+        engine.handle("position fen 4k3/8/8/8/8/8/8/4K2R w K - 0 1 moves e1g1");
+        assert_eq!(
+            "4k3/8/8/8/8/8/8/5RK1 b - - 1 1",
+            engine.position().to_string()
+        );
+        assert_eq!(
+            vec!["info string Move 0 (e2e5): e2e5 is not a legal move"],
+            engine.handle("position startpos moves e2e5")
+        );
+        assert_eq!(
+            vec!["info string Invalid FEN: no white king"],
+            engine.handle("position fen 4k3/8/8/8/8/8/8/8 w - - 0 1")
+        );
+        engine.handle("setoption name Hash value 32");
+        assert_eq!(Some("32"), engine.option("hash"));
+        assert_eq!(TranspositionTable::new(32).capacity(), engine.tt.capacity());
+        assert_eq!(
+            vec!["info string Invalid Hash size big"],
+            engine.handle("setoption name Hash value big")
+        );
+    }
+
+    #[test]
+    fn uci_engine_go() {
+        let mut engine = UciEngine::default();
+        engine.handle("position fen q3k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+        assert!(engine.handle("go depth 1").is_empty());
+        let response = engine.wait();
+        assert_eq!("bestmove a1a8", response[1]);
+        assert!(
+            response[0].starts_with("info depth 1 score cp "),
+            "{response:?}"
+        );
+        engine.handle("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+        engine.handle("go nodes 10000");
+        let response = engine.wait();
+        assert!(response[0].starts_with("info depth 1 score mate 1 nodes"));
+        assert!(response[0].contains(" hashfull "));
+        assert!(response[0].ends_with(" pv a1a8"));
+        engine.handle("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+        engine.handle("go movetime 10");
+        assert_eq!("bestmove 0000", engine.wait()[1]);
+        engine.handle("position startpos");
+        engine.handle("go wtime 200 btime 1 winc 0 binc 0");
+        assert!(engine.wait()[1].starts_with("bestmove "));
+        assert!(engine.wait().is_empty());
+    }
+
+    #[test]
+    fn time_allocation() {
+        let seconds = Duration::from_secs;
+        assert_eq!(seconds(2), allocate_time(seconds(60), seconds(0), None));
+        assert_eq!(seconds(15), allocate_time(seconds(60), seconds(4), Some(5)));
+        assert_eq!(seconds(5), allocate_time(seconds(10), seconds(8), Some(1)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn uci_engine_stop() {
+        let mut engine = UciEngine::default();
+        engine.handle("go infinite");
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(engine.poll().is_empty());
+        let response = engine.handle("stop");
+        assert!(response[1].starts_with("bestmove "), "{response:?}");
+        assert!(engine.handle("stop").is_empty());
+        engine.handle("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+        engine.handle("go depth 2");
+        let mut response = engine.poll();
+        while response.is_empty() {
+            std::thread::sleep(Duration::from_millis(1));
+            response = engine.poll();
+        }
+        assert_eq!("bestmove a1a8", response[1]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn uci_engine_run() {
+        let mut engine = UciEngine::default();
+        let mut output = alloc::vec::Vec::new();
+        engine
+            .run(&b"isready\nquit\nisready\n"[..], &mut output)
+            .unwrap();
+        assert_eq!(b"readyok\n", output.as_slice());
+        let mut output = alloc::vec::Vec::new();
+        engine
+            .run(&b"go infinite\nisready\nstop\nquit\n"[..], &mut output)
+            .unwrap();
+        let output = alloc::string::String::from_utf8(output).unwrap();
+        let lines: alloc::vec::Vec<&str> = output.lines().collect();
+        assert_eq!("readyok", lines[0]);
+        assert!(lines[2].starts_with("bestmove "), "{output}");
+        let mut output = alloc::vec::Vec::new();
+        engine
+            .run(&b"position startpos\ngo depth 1\n"[..], &mut output)
+            .unwrap();
+        assert!(alloc::string::String::from_utf8(output)
+            .unwrap()
+            .contains("bestmove "));
+    }
+}
@@ -0,0 +1,166 @@
+use core::hash::{Hash, Hasher};
+
+use crate::{squares, Color::*, GameState};
+
+struct ZobristKeys {
+    pieces: [[u64; 64]; 12],
+    black_to_move: u64,
+    castling: [u64; 4],
+    en_passant_file: [u64; 8],
+}
+
+const SEED: u64 = 0x4348_4552_5345_4421;
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+impl ZobristKeys {
+    const fn new(seed: u64) -> Self {
+        let mut state = seed;
+        let mut pieces = [[0; 64]; 12];
+        let mut piece = 0;
+        while piece < 12 {
+            let mut square = 0;
+            while square < 64 {
+                let (next, key) = splitmix64(state);
+                state = next;
+                pieces[piece][square] = key;
+                square += 1;
+            }
+            piece += 1;
+        }
+        let (next, black_to_move) = splitmix64(state);
+        state = next;
+        let mut castling = [0; 4];
+        let mut i = 0;
+        while i < 4 {
+            let (next, key) = splitmix64(state);
+            state = next;
+            castling[i] = key;
+            i += 1;
+        }
+        let mut en_passant_file = [0; 8];
+        let mut i = 0;
+        while i < 8 {
+            let (next, key) = splitmix64(state);
+            state = next;
+            en_passant_file[i] = key;
+            i += 1;
+        }
+        ZobristKeys {
+            pieces,
+            black_to_move,
+            castling,
+            en_passant_file,
+        }
+    }
+}
+
+static ZOBRIST_KEYS: ZobristKeys = ZobristKeys::new(SEED);
+
+impl GameState {
+    pub fn zobrist_hash(&self) -> u64 {
+        let keys = &ZOBRIST_KEYS;
+        let mut hash = 0;
+        for (piece, bitboard) in self.bitboards.iter().enumerate() {
+            for square in squares(*bitboard) {
+                hash ^= keys.pieces[piece][square];
+            }
+        }
+        if self.active_color == Black {
+            hash ^= keys.black_to_move;
+        }
+        for (right, key) in self.castling_rights.iter().zip(keys.castling) {
+            if *right {
+                hash ^= key;
+            }
+        }
+        if let Some(en_passant_target) = self
+            .en_passant_target
+            .filter(|_| self.en_passant_capture_available())
+        {
+            hash ^= keys.en_passant_file[en_passant_target as usize % 8];
+        }
+        hash
+    }
+}
+
+impl Hash for GameState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.zobrist_hash());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{GameState, Move};
+
+    #[test]
+    fn zobrist_hash_is_stable() {
+        assert_eq!(0x1D0D_EF6D_7CF9_D127, GameState::default().zobrist_hash());
+    }
+
+    #[test]
+    fn zobrist_hash_transpositions() {
+        let mut one = GameState::default();
+        for (from, to) in [(6, 21), (62, 45), (1, 18)] {
+            one = one.play_move(Move::new(from, to, None)).unwrap();
+        }
+        let mut two = GameState::default();
+        for (from, to) in [(1, 18), (62, 45), (6, 21)] {
+            two = two.play_move(Move::new(from, to, None)).unwrap();
+        }
+        assert_eq!(one.zobrist_hash(), two.zobrist_hash());
+        assert_ne!(GameState::default().zobrist_hash(), one.zobrist_hash());
+    }
+
+    #[test]
+    fn zobrist_hash_ignores_dangling_en_passant() {
+        let hash = |fen: &str| fen.parse::<GameState>().unwrap().zobrist_hash();
+        assert_eq!(
+            hash("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"),
+            hash("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")
+        );
+        assert_ne!(
+            hash("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3"),
+            hash("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3")
+        );
+    }
+
+    #[test]
+    fn zobrist_hash_make_unmake() {
+        let mut game_state = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+            .parse::<GameState>()
+            .unwrap();
+        let hash = game_state.zobrist_hash();
+        for mv in game_state.legal_moves() {
+            let undo = game_state.make_move(mv).unwrap();
+            assert_ne!(hash, game_state.zobrist_hash());
+            game_state.unmake_move(mv, undo);
+            assert_eq!(hash, game_state.zobrist_hash());
+        }
+    }
+
+    #[test]
+    fn game_states_as_hash_map_keys() {
+        let mut one = GameState::default();
+        for (from, to) in [(6, 21), (62, 45), (1, 18), (57, 42)] {
+            one = one.play_move(Move::new(from, to, None)).unwrap();
+        }
+        let mut other = GameState::default();
+        for (from, to) in [(1, 18), (57, 42), (6, 21), (62, 45)] {
+            other = other.play_move(Move::new(from, to, None)).unwrap();
+        }
+        let mut table = HashMap::new();
+        table.insert(one, 42);
+        assert_eq!(Some(&42), table.get(&other));
+        assert_eq!(None, table.get(&GameState::default()));
+    }
+}